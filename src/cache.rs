@@ -0,0 +1,412 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::RwLock;
+use twilight_model::{
+    channel::Channel,
+    gateway::{event::Event, payload::incoming::GuildCreate},
+    guild::{Guild, Member, PartialMember, Role},
+    id::{
+        Id,
+        marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+    },
+    user::User,
+    util::{ImageHash, Timestamp},
+};
+
+/// How long a cached member is trusted when no member events keep it current.
+const MEMBER_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Guild fields the bot needs without another HTTP call.
+#[derive(Clone, Debug)]
+pub struct CachedGuild {
+    pub name: String,
+    pub owner_id: Id<UserMarker>,
+    pub member_count: Option<u64>,
+}
+
+/// Guild member fields used for permission resolution.
+#[derive(Clone, Debug)]
+pub struct CachedMember {
+    pub roles: Vec<Id<RoleMarker>>,
+    pub communication_disabled_until: Option<Timestamp>,
+}
+
+/// User profile fields used for display in embeds.
+#[derive(Clone, Debug)]
+pub struct CachedUser {
+    pub name: String,
+    pub global_name: Option<String>,
+    pub avatar: Option<ImageHash>,
+}
+
+/// A cached member and when it was last seen.
+struct MemberEntry {
+    member: CachedMember,
+    cached_at: Instant,
+}
+
+#[derive(Default)]
+struct CacheState {
+    guilds: HashMap<Id<GuildMarker>, CachedGuild>,
+    roles: HashMap<Id<GuildMarker>, HashMap<Id<RoleMarker>, Role>>,
+    members: HashMap<(Id<GuildMarker>, Id<UserMarker>), MemberEntry>,
+    channels: HashMap<Id<ChannelMarker>, Channel>,
+    users: HashMap<Id<UserMarker>, CachedUser>,
+}
+
+/// In-memory guild, role, member, channel and user cache fed by gateway events.
+///
+/// Cheap to clone because it only stores reference-counted shared state.
+#[derive(Clone)]
+pub struct Cache {
+    state: Arc<RwLock<CacheState>>,
+    /// Whether the `GUILD_MEMBERS` intent delivers member updates and removals.
+    ///
+    /// Without it cached members expire after [`MEMBER_TTL`].
+    member_events: bool,
+}
+
+impl Cache {
+    /// Create an empty cache.
+    pub fn new(member_events: bool) -> Self {
+        Self {
+            state: Arc::default(),
+            member_events,
+        }
+    }
+
+    /// Apply a gateway event to the cache.
+    pub async fn update(&self, event: &Event) {
+        match event {
+            Event::GuildCreate(guild) => {
+                if let GuildCreate::Available(guild) = guild.as_ref() {
+                    let mut state = self.state.write().await;
+                    state.guilds.insert(
+                        guild.id,
+                        CachedGuild {
+                            name: guild.name.clone(),
+                            owner_id: guild.owner_id,
+                            member_count: guild.member_count,
+                        },
+                    );
+                    state.roles.insert(
+                        guild.id,
+                        guild
+                            .roles
+                            .iter()
+                            .map(|role| (role.id, role.clone()))
+                            .collect(),
+                    );
+                    for channel in guild.channels.iter().chain(&guild.threads) {
+                        let mut channel = channel.clone();
+                        channel.guild_id = Some(guild.id);
+                        state.channels.insert(channel.id, channel);
+                    }
+                    for member in &guild.members {
+                        state.insert_member(guild.id, member);
+                    }
+                }
+            }
+            Event::GuildUpdate(update) => {
+                let mut state = self.state.write().await;
+                if let Some(guild) = state.guilds.get_mut(&update.0.id) {
+                    guild.name.clone_from(&update.0.name);
+                    guild.owner_id = update.0.owner_id;
+                }
+                state.roles.insert(
+                    update.0.id,
+                    update
+                        .0
+                        .roles
+                        .iter()
+                        .map(|role| (role.id, role.clone()))
+                        .collect(),
+                );
+            }
+            Event::GuildDelete(delete) => {
+                let mut state = self.state.write().await;
+                state.guilds.remove(&delete.id);
+                state.roles.remove(&delete.id);
+                state
+                    .channels
+                    .retain(|_, channel| channel.guild_id != Some(delete.id));
+                state
+                    .members
+                    .retain(|(guild_id, _), _| *guild_id != delete.id);
+            }
+            Event::RoleCreate(create) => {
+                self.upsert_role(create.guild_id, create.role.clone()).await;
+            }
+            Event::RoleUpdate(update) => {
+                self.upsert_role(update.guild_id, update.role.clone()).await;
+            }
+            Event::RoleDelete(delete) => {
+                let mut state = self.state.write().await;
+                if let Some(roles) = state.roles.get_mut(&delete.guild_id) {
+                    roles.remove(&delete.role_id);
+                }
+            }
+            Event::MemberAdd(add) => {
+                let mut state = self.state.write().await;
+                state.insert_member(add.guild_id, &add.member);
+                if let Some(count) = state
+                    .guilds
+                    .get_mut(&add.guild_id)
+                    .and_then(|guild| guild.member_count.as_mut())
+                {
+                    *count += 1;
+                }
+            }
+            Event::MemberUpdate(update) => {
+                let mut state = self.state.write().await;
+                state.insert_user(&update.user);
+                state.store_member(
+                    update.guild_id,
+                    update.user.id,
+                    CachedMember {
+                        roles: update.roles.clone(),
+                        communication_disabled_until: update.communication_disabled_until,
+                    },
+                );
+            }
+            Event::MemberRemove(remove) => {
+                let mut state = self.state.write().await;
+                state.members.remove(&(remove.guild_id, remove.user.id));
+                if let Some(count) = state
+                    .guilds
+                    .get_mut(&remove.guild_id)
+                    .and_then(|guild| guild.member_count.as_mut())
+                {
+                    *count = count.saturating_sub(1);
+                }
+            }
+            Event::MemberChunk(chunk) => {
+                let mut state = self.state.write().await;
+                for member in &chunk.members {
+                    state.insert_member(chunk.guild_id, member);
+                }
+            }
+            Event::ChannelCreate(channel) => self.upsert_channel(&channel.0).await,
+            Event::ChannelUpdate(channel) => self.upsert_channel(&channel.0).await,
+            Event::ChannelDelete(channel) => self.remove_channel(channel.0.id).await,
+            Event::ThreadCreate(thread) => self.upsert_channel(&thread.0).await,
+            Event::ThreadUpdate(thread) => self.upsert_channel(&thread.0).await,
+            Event::ThreadDelete(thread) => self.remove_channel(thread.id).await,
+            Event::MessageCreate(msg) => {
+                let mut state = self.state.write().await;
+                state.insert_user(&msg.author);
+                if let (Some(guild_id), Some(member)) = (msg.guild_id, msg.member.as_ref()) {
+                    state.insert_partial_member(guild_id, msg.author.id, member);
+                }
+            }
+            Event::UserUpdate(user) => {
+                let mut state = self.state.write().await;
+                if let Some(cached) = state.users.get_mut(&user.id) {
+                    cached.name.clone_from(&user.name);
+                    cached.avatar = user.avatar;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Cached guild metadata.
+    pub async fn guild(&self, guild_id: Id<GuildMarker>) -> Option<CachedGuild> {
+        self.state.read().await.guilds.get(&guild_id).cloned()
+    }
+
+    /// All cached guilds, i.e. every guild the bot is in once shards are ready.
+    pub async fn guilds(&self) -> Vec<(Id<GuildMarker>, CachedGuild)> {
+        self.state
+            .read()
+            .await
+            .guilds
+            .iter()
+            .map(|(guild_id, guild)| (*guild_id, guild.clone()))
+            .collect()
+    }
+
+    /// Store a guild fetched over HTTP.
+    pub async fn insert_guild(&self, guild: &Guild) {
+        self.state.write().await.guilds.insert(
+            guild.id,
+            CachedGuild {
+                name: guild.name.clone(),
+                owner_id: guild.owner_id,
+                member_count: guild.approximate_member_count.or(guild.member_count),
+            },
+        );
+    }
+
+    /// All cached roles of a guild, or `None` when the guild's roles are unknown.
+    pub async fn roles(&self, guild_id: Id<GuildMarker>) -> Option<Vec<Role>> {
+        self.state
+            .read()
+            .await
+            .roles
+            .get(&guild_id)
+            .map(|roles| roles.values().cloned().collect())
+    }
+
+    /// Replace the cached role list of a guild (e.g. after an HTTP fetch).
+    pub async fn set_roles(&self, guild_id: Id<GuildMarker>, roles: &[Role]) {
+        self.state.write().await.roles.insert(
+            guild_id,
+            roles.iter().map(|role| (role.id, role.clone())).collect(),
+        );
+    }
+
+    /// Cached guild member, unless it may have gone stale.
+    pub async fn member(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Option<CachedMember> {
+        self.state
+            .read()
+            .await
+            .members
+            .get(&(guild_id, user_id))
+            .filter(|entry| self.member_events || entry.cached_at.elapsed() < MEMBER_TTL)
+            .map(|entry| entry.member.clone())
+    }
+
+    /// Store a member fetched over HTTP or returned by a member update.
+    pub async fn insert_member(&self, guild_id: Id<GuildMarker>, member: &Member) {
+        self.state.write().await.insert_member(guild_id, member);
+    }
+
+    /// Replace the cached members of a guild (e.g. after listing them all over HTTP).
+    pub async fn set_members(&self, guild_id: Id<GuildMarker>, members: &[Member]) {
+        let mut state = self.state.write().await;
+        state
+            .members
+            .retain(|(member_guild_id, _), _| *member_guild_id != guild_id);
+        for member in members {
+            state.insert_member(guild_id, member);
+        }
+    }
+
+    /// Forget a member the bot removed from a guild.
+    pub async fn remove_member(&self, guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) {
+        self.state
+            .write()
+            .await
+            .members
+            .remove(&(guild_id, user_id));
+    }
+
+    /// Cached members of a guild, keyed by user ID, however old.
+    pub async fn members(&self, guild_id: Id<GuildMarker>) -> Vec<(Id<UserMarker>, CachedMember)> {
+        self.state
+            .read()
+            .await
+            .members
+            .iter()
+            .filter(|((member_guild_id, _), _)| *member_guild_id == guild_id)
+            .map(|((_, user_id), entry)| (*user_id, entry.member.clone()))
+            .collect()
+    }
+
+    /// Cached channel or thread.
+    pub async fn channel(&self, channel_id: Id<ChannelMarker>) -> Option<Channel> {
+        self.state.read().await.channels.get(&channel_id).cloned()
+    }
+
+    /// Store a channel fetched over HTTP.
+    pub async fn insert_channel(&self, channel: &Channel) {
+        self.upsert_channel(channel).await;
+    }
+
+    /// Cached user profile.
+    pub async fn user(&self, user_id: Id<UserMarker>) -> Option<CachedUser> {
+        self.state.read().await.users.get(&user_id).cloned()
+    }
+
+    /// Store a user fetched over HTTP.
+    pub async fn insert_user(&self, user: &User) {
+        self.state.write().await.insert_user(user);
+    }
+
+    async fn upsert_role(&self, guild_id: Id<GuildMarker>, role: Role) {
+        self.state
+            .write()
+            .await
+            .roles
+            .entry(guild_id)
+            .or_default()
+            .insert(role.id, role);
+    }
+
+    async fn upsert_channel(&self, channel: &Channel) {
+        self.state
+            .write()
+            .await
+            .channels
+            .insert(channel.id, channel.clone());
+    }
+
+    async fn remove_channel(&self, channel_id: Id<ChannelMarker>) {
+        self.state.write().await.channels.remove(&channel_id);
+    }
+}
+
+impl CacheState {
+    fn insert_member(&mut self, guild_id: Id<GuildMarker>, member: &Member) {
+        self.insert_user(&member.user);
+        self.store_member(
+            guild_id,
+            member.user.id,
+            CachedMember {
+                roles: member.roles.clone(),
+                communication_disabled_until: member.communication_disabled_until,
+            },
+        );
+    }
+
+    fn insert_partial_member(
+        &mut self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        member: &PartialMember,
+    ) {
+        self.store_member(
+            guild_id,
+            user_id,
+            CachedMember {
+                roles: member.roles.clone(),
+                communication_disabled_until: member.communication_disabled_until,
+            },
+        );
+    }
+
+    fn store_member(
+        &mut self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        member: CachedMember,
+    ) {
+        self.members.insert(
+            (guild_id, user_id),
+            MemberEntry {
+                member,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    fn insert_user(&mut self, user: &User) {
+        self.users.insert(
+            user.id,
+            CachedUser {
+                name: user.name.clone(),
+                global_name: user.global_name.clone(),
+                avatar: user.avatar,
+            },
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use twilight_model::{
+    gateway::payload::incoming::MessageCreate,
+    guild::{Permissions, Role},
+};
+
+use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
+use crate::commands::registry::command;
+use crate::context::Context;
+use crate::database::command_access::{
+    AccessMode, CommandAccess, command_access, guild_access, update_access,
+};
+use crate::util::permissions::guild_roles;
+
+pub const META: CommandMeta = CommandMeta {
+    name: "access",
+    desc: "Grant roles access to commands regardless of Discord permissions.",
+    category: "admin",
+    usage: "!access list | !access <command> [reset] | !access <command> grant|revoke <role> | !access <command> mode extend|replace",
+    aliases: &[],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MANAGE_GUILD,
+    subcommands: &[],
+};
+
+command!(AccessCommand => run);
+
+/// Manage per-guild role overrides for command permissions.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
+
+    let mut args = args.parser(&ctx, &msg);
+    if args
+        .optional(|args| args.choice("command", &["list"]))
+        .is_some()
+    {
+        let roles = guild_roles(&ctx, guild_id).await?;
+        let overrides = guild_access(guild_id.get()).await;
+        let out = access_list(&overrides, &roles);
+        http.create_message(msg.channel_id).content(&out).await?;
+        return Ok(());
+    }
+
+    let command = args.command("command")?;
+
+    // Granting this command would let a role hand itself access to everything else.
+    if command.name == META.name {
+        http.create_message(msg.channel_id)
+            .content("Access to this command can't be overridden.")
+            .await?;
+        return Ok(());
+    }
+
+    let guild = guild_id.get();
+    let access = if args.is_empty() {
+        command_access(guild, command.name).await
+    } else {
+        match args.choice("action", &["reset", "mode", "grant", "revoke"])? {
+            "reset" => {
+                update_access(guild, command.name, |access| {
+                    *access = CommandAccess::default();
+                })
+                .await
+            }
+            "mode" => {
+                let mode = match args.choice("mode", &["extend", "replace"])? {
+                    "extend" => AccessMode::Extend,
+                    _ => AccessMode::Replace,
+                };
+                update_access(guild, command.name, |access| access.mode = mode).await
+            }
+            action => {
+                let role_id = args.role("role").await?.id.get();
+                update_access(guild, command.name, |access| {
+                    if action == "grant" {
+                        access.roles.insert(role_id);
+                    } else {
+                        access.roles.remove(&role_id);
+                    }
+                })
+                .await
+            }
+        }
+    };
+
+    let roles = guild_roles(&ctx, guild_id).await?;
+    let out = format!(
+        "Access for `{}`:\n{}",
+        command.name,
+        access_summary(&access, &roles)
+    );
+    http.create_message(msg.channel_id).content(&out).await?;
+
+    Ok(())
+}
+
+fn access_list(overrides: &BTreeMap<String, CommandAccess>, roles: &[Role]) -> String {
+    if overrides.is_empty() {
+        return "No command access overrides are set in this server.".to_owned();
+    }
+
+    overrides
+        .iter()
+        .map(|(command, access)| format!("**{}**\n{}", command, access_summary(access, roles)))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn access_summary(access: &CommandAccess, roles: &[Role]) -> String {
+    if access.roles.is_empty() {
+        return format!("Default permission only (mode: {}).", access.mode.label());
+    }
+
+    // Role names rather than mentions so listing them never pings anyone.
+    let granted = access
+        .roles
+        .iter()
+        .map(|id| match roles.iter().find(|role| role.id.get() == *id) {
+            Some(role) => format!("`{}`", role.name),
+            None => format!("`deleted role {}`", id),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mode = match access.mode {
+        AccessMode::Extend => "in addition to the default permission",
+        AccessMode::Replace => "instead of the default permission",
+    };
+
+    format!("Granted roles: {} ({})", granted, mode)
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
+
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, find_command};
+use crate::context::Context;
+use crate::database::aliases::{MAX_ALIASES_PER_GUILD, guild_aliases, remove_alias, set_alias};
+
+pub const META: CommandMeta = CommandMeta {
+    name: "alias",
+    desc: "Manage this server's custom command aliases.",
+    category: "admin",
+    usage: "!alias list | !alias add <alias> <command> | !alias remove <alias>",
+    aliases: &[],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MANAGE_GUILD,
+    subcommands: &[],
+};
+
+command!(AliasCommand => run);
+
+const MAX_ALIAS_CHARS: usize = 20;
+
+/// List, add and remove per-guild command aliases.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
+
+    let mut args = args.parser(&ctx, &msg);
+    let out = match args.choice("action", &["list", "add", "remove"])? {
+        "list" => {
+            let aliases = guild_aliases(Some(guild_id.get())).await;
+            alias_list(&aliases)
+        }
+        "add" => {
+            let alias = args.word("alias")?.to_ascii_lowercase();
+            let command = args.command("command")?;
+            add_alias(guild_id.get(), &alias, command).await
+        }
+        _ => {
+            let alias = args.word("alias")?.to_ascii_lowercase();
+            match remove_alias(guild_id.get(), &alias).await {
+                Some(command) => format!("Removed alias `{}` for `{}`.", alias, command),
+                None => format!("No custom alias named `{}`.", alias),
+            }
+        }
+    };
+
+    http.create_message(msg.channel_id).content(&out).await?;
+
+    Ok(())
+}
+
+async fn add_alias(guild_id: u64, alias: &str, command: &'static CommandMeta) -> String {
+    let valid_name = (1..=MAX_ALIAS_CHARS).contains(&alias.chars().count())
+        && alias
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    if !valid_name {
+        return format!(
+            "Aliases must be 1 to {} letters, digits, `-` or `_`.",
+            MAX_ALIAS_CHARS
+        );
+    }
+
+    if let Some(existing) = find_command(alias, &BTreeMap::new()) {
+        return format!("`{}` is already taken by `{}`.", alias, existing.name);
+    }
+
+    if !set_alias(guild_id, alias, command.name).await {
+        return format!(
+            "This server already has the maximum of {} aliases.",
+            MAX_ALIASES_PER_GUILD
+        );
+    }
+
+    format!("`{}` now runs `{}`.", alias, command.name)
+}
+
+fn alias_list(aliases: &BTreeMap<String, String>) -> String {
+    if aliases.is_empty() {
+        return "This server has no custom aliases.".to_owned();
+    }
+
+    let lines = aliases
+        .iter()
+        .map(|(alias, command)| format!("`{}` → `{}`", alias, command))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!("Custom aliases:\n{}", lines)
+}
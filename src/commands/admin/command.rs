@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use twilight_model::{
+    gateway::payload::incoming::MessageCreate,
+    guild::{Permissions, Role},
+};
+
+use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
+use crate::commands::registry::command;
+use crate::context::Context;
+use crate::database::command_policy::{
+    CommandPolicy, PolicyRefusal, command_policy, guild_policies, update_policy,
+};
+use crate::util::permissions::guild_roles;
+
+pub const META: CommandMeta = CommandMeta {
+    name: "command",
+    desc: "Enable, disable or restrict commands in this server.",
+    category: "admin",
+    usage: "!command list | !command <name> [enable|disable|reset] | !command <name> allow|deny|unset channel|role <target>",
+    aliases: &["cmd"],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MANAGE_GUILD,
+    subcommands: &[],
+};
+
+command!(CommandCommand => run);
+
+/// Refuse a command when the guild's policy forbids it here.
+///
+/// Returns `Ok(true)` when the command may run. This command itself is exempt
+/// so a guild can never lock itself out of its own policy.
+pub async fn check_policy(
+    ctx: &Context,
+    msg: &MessageCreate,
+    command: &CommandMeta,
+    prefix: &str,
+) -> anyhow::Result<bool> {
+    let Some(guild_id) = msg.guild_id else {
+        return Ok(true);
+    };
+
+    if command.name == META.name {
+        return Ok(true);
+    }
+
+    let policy = command_policy(guild_id.get(), command.name).await;
+    if policy == CommandPolicy::default() {
+        return Ok(true);
+    }
+
+    let mut channel_ids = vec![msg.channel_id.get()];
+    if let Some(channel) = ctx.cache.channel(msg.channel_id).await
+        && channel.kind.is_thread()
+        && let Some(parent_id) = channel.parent_id
+    {
+        channel_ids.push(parent_id.get());
+    }
+
+    // Every member holds @everyone, whose role ID is the guild ID
+    let mut role_ids = vec![guild_id.get()];
+    if let Some(member) = &msg.member {
+        role_ids.extend(member.roles.iter().map(|id| id.get()));
+    }
+
+    let Some(refusal) = policy.check(&channel_ids, &role_ids) else {
+        return Ok(true);
+    };
+
+    let label = format!("{}{}", prefix, command.name);
+    let out = match refusal {
+        PolicyRefusal::Disabled => format!("`{}` is disabled in this server.", label),
+        PolicyRefusal::ChannelDenied => format!("`{}` can't be used in this channel.", label),
+        PolicyRefusal::ChannelNotAllowed(allowed) => format!(
+            "`{}` can only be used in {}.",
+            label,
+            channel_mentions(allowed.iter())
+        ),
+        PolicyRefusal::RoleDenied => format!("One of your roles may not use `{}`.", label),
+        PolicyRefusal::RoleNotAllowed => {
+            format!("You don't have a role that is allowed to use `{}`.", label)
+        }
+    };
+    ctx.http
+        .create_message(msg.channel_id)
+        .content(&out)
+        .await?;
+
+    Ok(false)
+}
+
+/// Manage per-guild command policies.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
+
+    let mut args = args.parser(&ctx, &msg);
+    if args
+        .optional(|args| args.choice("name", &["list"]))
+        .is_some()
+    {
+        let policies = guild_policies(guild_id.get()).await;
+        let roles = guild_roles(&ctx, guild_id).await?;
+        let out = policy_list(&policies, &roles);
+        http.create_message(msg.channel_id).content(&out).await?;
+        return Ok(());
+    }
+
+    let command = args.command("name")?;
+
+    if command.name == META.name {
+        http.create_message(msg.channel_id)
+            .content("This command can't be restricted.")
+            .await?;
+        return Ok(());
+    }
+
+    let guild = guild_id.get();
+    let action = if args.is_empty() {
+        "show"
+    } else {
+        args.choice(
+            "action",
+            &[
+                "show", "enable", "disable", "reset", "allow", "deny", "unset",
+            ],
+        )?
+    };
+
+    let policy = match action {
+        "show" => command_policy(guild, command.name).await,
+        "enable" => update_policy(guild, command.name, |policy| policy.disabled = false).await,
+        "disable" => update_policy(guild, command.name, |policy| policy.disabled = true).await,
+        "reset" => {
+            update_policy(guild, command.name, |policy| {
+                *policy = CommandPolicy::default();
+            })
+            .await
+        }
+        action => match args.choice("channel|role", &["channel", "role"])? {
+            "channel" => {
+                let channel_id = args.channel("target").await?.id.get();
+                update_policy(guild, command.name, |policy| {
+                    apply_list_action(
+                        action,
+                        channel_id,
+                        &mut policy.allowed_channels,
+                        &mut policy.denied_channels,
+                    );
+                })
+                .await
+            }
+            _ => {
+                let role_id = args.role("target").await?.id.get();
+                update_policy(guild, command.name, |policy| {
+                    apply_list_action(
+                        action,
+                        role_id,
+                        &mut policy.allowed_roles,
+                        &mut policy.denied_roles,
+                    );
+                })
+                .await
+            }
+        },
+    };
+
+    let roles = guild_roles(&ctx, guild_id).await?;
+    let out = format!(
+        "Policy for `{}`:\n{}",
+        command.name,
+        policy_summary(&policy, &roles)
+    );
+    http.create_message(msg.channel_id).content(&out).await?;
+
+    Ok(())
+}
+
+/// Move an ID between an allow and a deny list, or drop it from both.
+fn apply_list_action(
+    action: &str,
+    id: u64,
+    allowed: &mut BTreeSet<u64>,
+    denied: &mut BTreeSet<u64>,
+) {
+    allowed.remove(&id);
+    denied.remove(&id);
+
+    match action {
+        "allow" => {
+            allowed.insert(id);
+        }
+        "deny" => {
+            denied.insert(id);
+        }
+        _ => {}
+    }
+}
+
+fn policy_list(policies: &BTreeMap<String, CommandPolicy>, roles: &[Role]) -> String {
+    if policies.is_empty() {
+        return "No command restrictions are set in this server.".to_owned();
+    }
+
+    policies
+        .iter()
+        .map(|(command, policy)| format!("**{}**\n{}", command, policy_summary(policy, roles)))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn policy_summary(policy: &CommandPolicy, roles: &[Role]) -> String {
+    if *policy == CommandPolicy::default() {
+        return "No restrictions.".to_owned();
+    }
+
+    let mut lines = Vec::new();
+    if policy.disabled {
+        lines.push("Disabled".to_owned());
+    }
+    if !policy.allowed_channels.is_empty() {
+        lines.push(format!(
+            "Allowed channels: {}",
+            channel_mentions(policy.allowed_channels.iter())
+        ));
+    }
+    if !policy.denied_channels.is_empty() {
+        lines.push(format!(
+            "Denied channels: {}",
+            channel_mentions(policy.denied_channels.iter())
+        ));
+    }
+    if !policy.allowed_roles.is_empty() {
+        lines.push(format!(
+            "Allowed roles: {}",
+            role_names(policy.allowed_roles.iter(), roles)
+        ));
+    }
+    if !policy.denied_roles.is_empty() {
+        lines.push(format!(
+            "Denied roles: {}",
+            role_names(policy.denied_roles.iter(), roles)
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn channel_mentions<'a>(ids: impl Iterator<Item = &'a u64>) -> String {
+    ids.map(|id| format!("<#{}>", id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Roles are listed by name rather than mention so listing them never pings anyone.
+fn role_names<'a>(ids: impl Iterator<Item = &'a u64>, roles: &[Role]) -> String {
+    ids.map(|id| match roles.iter().find(|role| role.id.get() == *id) {
+        Some(role) => format!("`{}`", role.name),
+        None => format!("`deleted role {}`", id),
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
@@ -0,0 +1,168 @@
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
+use twilight_util::builder::embed::EmbedBuilder;
+
+use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
+use crate::commands::registry::command;
+use crate::context::Context;
+use crate::database::guild_config::{
+    ConfigKey, custom_keys, guild_config, reset_config_value, set_config_value,
+};
+
+pub const META: CommandMeta = CommandMeta {
+    name: "config",
+    desc: "View or change this server's bot settings.",
+    category: "admin",
+    usage: "!config list | !config get <key> | !config set <key> <value> | !config reset <key>",
+    aliases: &[],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MANAGE_GUILD,
+    subcommands: &[LIST_META, GET_META, SET_META, RESET_META],
+};
+
+command!(ConfigCommand => run);
+
+const LIST_META: CommandMeta = CommandMeta {
+    name: "list",
+    desc: "Show every setting and whether it was changed.",
+    category: "admin",
+    usage: "!config list",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+const GET_META: CommandMeta = CommandMeta {
+    name: "get",
+    desc: "Show one setting.",
+    category: "admin",
+    usage: "!config get <key>",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+const SET_META: CommandMeta = CommandMeta {
+    name: "set",
+    desc: "Change one setting.",
+    category: "admin",
+    usage: "!config set <key> <value>",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+const RESET_META: CommandMeta = CommandMeta {
+    name: "reset",
+    desc: "Restore one setting to the bot-wide default.",
+    category: "admin",
+    usage: "!config reset <key>",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+/// View and edit per-guild settings.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
+
+    let config = guild_config(Some(guild_id.get())).await;
+    let custom = custom_keys(guild_id.get()).await;
+    let action = match args.subcommand_path().as_slice() {
+        [action] => *action,
+        _ => {
+            let usage = META.usage_line(&config.prefix);
+            http.create_message(msg.channel_id).content(&usage).await?;
+            return Ok(());
+        }
+    };
+
+    if action == "list" {
+        let description = ConfigKey::ALL
+            .into_iter()
+            .map(|key| config_line(&config.display(key), key, !custom.contains(&key)))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let embed = EmbedBuilder::new()
+            .color(config.embed_color)
+            .title("Server Settings")
+            .description(description)
+            .validate()?
+            .build();
+        http.create_message(msg.channel_id).embeds(&[embed]).await?;
+        return Ok(());
+    }
+
+    let mut args = args.parser(&ctx, &msg);
+    let raw_key = args.word("key")?;
+    let Some(key) = ConfigKey::parse(raw_key) else {
+        let known = ConfigKey::ALL
+            .into_iter()
+            .map(|key| format!("`{}`", key.name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let out = format!("Unknown setting `{}`. Known settings: {}", raw_key, known);
+        http.create_message(msg.channel_id).content(&out).await?;
+        return Ok(());
+    };
+
+    let out = match action {
+        "set" => {
+            let raw_value = args.rest("value")?;
+            match key.parse_value(&raw_value) {
+                Some(value) => {
+                    set_config_value(guild_id.get(), value).await;
+                    let updated = guild_config(Some(guild_id.get())).await;
+                    format!("Set `{}` to `{}`.", key.name(), updated.display(key))
+                }
+                None => format!(
+                    "Invalid value for `{}`: expected {}.",
+                    key.name(),
+                    key.expected()
+                ),
+            }
+        }
+        "reset" => {
+            reset_config_value(guild_id.get(), key).await;
+            let updated = guild_config(Some(guild_id.get())).await;
+            format!(
+                "Reset `{}` to its default `{}`.",
+                key.name(),
+                updated.display(key)
+            )
+        }
+        _ => config_line(&config.display(key), key, !custom.contains(&key)),
+    };
+
+    http.create_message(msg.channel_id).content(&out).await?;
+
+    Ok(())
+}
+
+fn config_line(value: &str, key: ConfigKey, is_default: bool) -> String {
+    let origin = if is_default { "default" } else { "custom" };
+    format!(
+        "`{}` = `{}` ({})\n{}",
+        key.name(),
+        value,
+        origin,
+        key.description()
+    )
+}
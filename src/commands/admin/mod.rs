@@ -0,0 +1,4 @@
+pub mod access;
+pub mod alias;
+pub mod command;
+pub mod config;
@@ -0,0 +1,652 @@
+//! Typed parsing of the text after a command name.
+//!
+//! Commands pull arguments off an [`Args`] in usage order and propagate failures
+//! with `?`; the dispatcher turns an [`ArgError`] into a reply naming the bad
+//! argument followed by the command's usage. Lookups that fail for any reason
+//! other than Discord not knowing the ID return the underlying error instead.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use twilight_http::Error as HttpError;
+use twilight_model::{
+    channel::Channel,
+    gateway::payload::incoming::MessageCreate,
+    guild::Role,
+    id::{
+        Id,
+        marker::{GuildMarker, UserMarker},
+    },
+};
+
+use crate::cache::CachedMember;
+use crate::commands::{CommandMeta, find_command};
+use crate::context::Context;
+use crate::error::BotError;
+use crate::util::parse::{
+    find_role, format_duration_seconds, parse_channel_id, parse_duration_seconds, parse_guild_id,
+    parse_role_id, parse_target_user_id,
+};
+use crate::util::permissions::{fetch_channel, guild_member, guild_roles};
+
+/// Highest page number accepted by [`Args::page`], short enough to tell apart from an ID.
+const MAX_PAGE: usize = 999_999;
+
+/// Text after the command name, as typed and split into [`tokenize`]d words.
+#[derive(Clone, Debug, Default)]
+pub struct CommandArgs {
+    raw: String,
+    tokens: Vec<Token>,
+    subcommands: Vec<&'static CommandMeta>,
+}
+
+impl CommandArgs {
+    pub fn new(raw: &str) -> Self {
+        let raw = raw.trim();
+        Self {
+            raw: raw.to_owned(),
+            tokens: tokenize(raw),
+            subcommands: Vec::new(),
+        }
+    }
+
+    /// Consume leading subcommand names of `command` and return the deepest match.
+    ///
+    /// Returns `command` itself when the first word isn't one of its subcommands.
+    pub fn resolve_subcommands(&mut self, command: &'static CommandMeta) -> &'static CommandMeta {
+        let mut current = command;
+        while let Some(first) = self.tokens.first()
+            && let Some(sub) = current.subcommand(&first.text)
+        {
+            self.tokens.remove(0);
+            self.subcommands.push(sub);
+            current = sub;
+        }
+
+        current
+    }
+
+    /// Subcommands resolved by the dispatcher, outermost first.
+    pub fn subcommands(&self) -> &[&'static CommandMeta] {
+        &self.subcommands
+    }
+
+    /// Names of the resolved subcommands, e.g. `["clear"]` for `!warnings clear`.
+    pub fn subcommand_path(&self) -> Vec<&'static str> {
+        self.subcommands.iter().map(|sub| sub.name).collect()
+    }
+
+    /// The input after any subcommand names, as typed.
+    fn unparsed(&self) -> &str {
+        self.tokens
+            .first()
+            .map_or("", |token| &self.raw[token.start..])
+    }
+
+    /// First word, for commands that split their arguments by hand.
+    pub fn arg1(&self) -> Option<&str> {
+        self.unparsed().split_whitespace().next()
+    }
+
+    /// Everything after the first word.
+    pub fn arg_tail(&self) -> Option<&str> {
+        self.unparsed()
+            .split_once(char::is_whitespace)
+            .map(|(_, tail)| tail.trim())
+            .filter(|tail| !tail.is_empty())
+    }
+
+    /// Start typed parsing of these arguments.
+    pub fn parser<'a>(&'a self, ctx: &'a Context, msg: &'a MessageCreate) -> Args<'a> {
+        Args {
+            ctx,
+            guild_id: msg.guild_id,
+            raw: &self.raw,
+            tokens: self
+                .tokens
+                .iter()
+                .map(|token| Word {
+                    text: &token.text,
+                    start: token.start,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What was wrong with an argument.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgProblem {
+    Missing,
+    Invalid { got: String, expected: String },
+}
+
+/// An argument that couldn't be parsed, named as in the command's usage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArgError {
+    pub name: &'static str,
+    pub problem: ArgProblem,
+}
+
+impl ArgError {
+    fn missing(name: &'static str) -> Self {
+        Self {
+            name,
+            problem: ArgProblem::Missing,
+        }
+    }
+
+    fn invalid(name: &'static str, got: &str, expected: impl Into<String>) -> Self {
+        Self {
+            name,
+            problem: ArgProblem::Invalid {
+                got: got.to_owned(),
+                expected: expected.into(),
+            },
+        }
+    }
+
+    /// Reply text: the problem, then the usage line.
+    pub fn reply(&self, usage: &str) -> String {
+        format!("{}\nUsage: `{}`", self, usage)
+    }
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            ArgProblem::Missing => write!(f, "Missing `<{}>`.", self.name),
+            ArgProblem::Invalid { got, expected } => write!(
+                f,
+                "Invalid `<{}>`: expected {}, got `{}`.",
+                self.name, expected, got
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// Report a failed lookup as `invalid` only when Discord doesn't know the ID.
+///
+/// Anything else, such as a rate limit or an outage, is a real failure and is
+/// passed on classified so it gets logged and explained.
+fn lookup_error(source: anyhow::Error, invalid: ArgError) -> anyhow::Error {
+    match source.downcast::<HttpError>().map(BotError::from) {
+        Ok(BotError::UnknownMember(_) | BotError::UnknownUser(_) | BotError::UnknownChannel(_)) => {
+            invalid.into()
+        }
+        Ok(bot_error) => bot_error.into(),
+        Err(source) => source,
+    }
+}
+
+/// A word of the input with quotes and escapes resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    /// Byte offset in the input where the word, including any quote, starts.
+    pub start: usize,
+}
+
+/// Split arguments into words, keeping double-quoted text together.
+///
+/// `"spam links"` is one token; `\"`, `\\` and a backslash before a space are
+/// taken literally. An unclosed quote runs to the end of the input.
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut start = None;
+    let mut quoted = false;
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '\\' if chars
+                .peek()
+                .is_some_and(|(_, next)| *next == '"' || *next == '\\' || next.is_whitespace()) =>
+            {
+                current.extend(chars.next().map(|(_, next)| next));
+                start.get_or_insert(index);
+            }
+            '"' => {
+                quoted = !quoted;
+                start.get_or_insert(index);
+            }
+            ch if ch.is_whitespace() && !quoted => {
+                if let Some(start) = start.take() {
+                    tokens.push(Token {
+                        text: std::mem::take(&mut current),
+                        start,
+                    });
+                }
+            }
+            ch => {
+                current.push(ch);
+                start.get_or_insert(index);
+            }
+        }
+    }
+
+    if let Some(start) = start {
+        tokens.push(Token {
+            text: current,
+            start,
+        });
+    }
+
+    tokens
+}
+
+/// A token borrowed by [`Args`], with its offset in the raw input.
+#[derive(Clone, Copy, Debug)]
+struct Word<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+/// The input from the first of `words` on, or that word alone if it is the
+/// only one left and was quoted.
+fn remaining_text(raw: &str, words: &VecDeque<Word<'_>>) -> Option<String> {
+    let first = words.front()?;
+    let typed = &raw[first.start..];
+    if words.len() == 1 && typed.starts_with('"') {
+        return Some(first.text.to_owned());
+    }
+
+    Some(typed.to_owned())
+}
+
+/// Cursor over a command's arguments.
+///
+/// Arguments are taken from the front in usage order. Options and switches
+/// only match at the cursor too, so they end at the first positional argument
+/// and never swallow words of a free-text reason.
+pub struct Args<'a> {
+    ctx: &'a Context,
+    guild_id: Option<Id<GuildMarker>>,
+    raw: &'a str,
+    tokens: VecDeque<Word<'a>>,
+}
+
+impl<'a> Args<'a> {
+    /// Whether every argument has been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The next word, without consuming it.
+    pub fn peek(&self) -> Option<&'a str> {
+        self.tokens.front().map(|word| word.text)
+    }
+
+    fn next_word(&mut self, name: &'static str) -> Result<&'a str, ArgError> {
+        self.tokens
+            .pop_front()
+            .map(|word| word.text)
+            .ok_or_else(|| ArgError::missing(name))
+    }
+
+    /// A `--name value` or `--name=value` option at the cursor, parsed with `parse`.
+    pub fn option<T>(
+        &mut self,
+        name: &'static str,
+        parse: impl FnOnce(&mut Self) -> Result<T, ArgError>,
+    ) -> Result<Option<T>, ArgError> {
+        let Some(front) = self.tokens.front() else {
+            return Ok(None);
+        };
+        let (key, inline_value) = match front.text.split_once('=') {
+            Some((key, value)) => (key, Some(value)),
+            None => (front.text, None),
+        };
+        if !key.eq_ignore_ascii_case(&format!("--{}", name)) {
+            return Ok(None);
+        }
+
+        self.tokens.pop_front();
+        let value = match inline_value {
+            Some(value) => value,
+            None => self.next_word(name)?,
+        };
+
+        let mut value_args = Args {
+            ctx: self.ctx,
+            guild_id: self.guild_id,
+            raw: value,
+            tokens: VecDeque::from([Word {
+                text: value,
+                start: 0,
+            }]),
+        };
+        parse(&mut value_args).map(Some)
+    }
+
+    /// Whether a `--name` or `-s` switch is at the cursor; it is consumed if so.
+    pub fn switch(&mut self, name: &'static str, short: char) -> bool {
+        let matched = self.tokens.front().is_some_and(|word| {
+            word.text.eq_ignore_ascii_case(&format!("--{}", name))
+                || word.text == format!("-{}", short)
+        });
+        if matched {
+            self.tokens.pop_front();
+        }
+
+        matched
+    }
+
+    /// A single word, as typed.
+    pub fn word(&mut self, name: &'static str) -> Result<&'a str, ArgError> {
+        self.next_word(name)
+    }
+
+    /// A user mention or ID; the user doesn't need to be in the server.
+    pub fn user(&mut self, name: &'static str) -> Result<Id<UserMarker>, ArgError> {
+        let raw = self.next_word(name)?;
+        parse_target_user_id(raw)
+            .ok_or_else(|| ArgError::invalid(name, raw, "a user mention or ID"))
+    }
+
+    /// A user mention or ID that must belong to a member of this server.
+    pub async fn member(
+        &mut self,
+        name: &'static str,
+    ) -> anyhow::Result<(Id<UserMarker>, CachedMember)> {
+        let raw = self.next_word(name)?;
+        let expected = "a member of this server";
+        let (Some(guild_id), Some(user_id)) = (self.guild_id, parse_target_user_id(raw)) else {
+            return Err(ArgError::invalid(name, raw, expected).into());
+        };
+
+        match guild_member(self.ctx, guild_id, user_id).await {
+            Ok(member) => Ok((user_id, member)),
+            Err(source) => Err(lookup_error(source, ArgError::invalid(name, raw, expected))),
+        }
+    }
+
+    /// A role mention, ID or name in this server.
+    ///
+    /// Names may be quoted or typed with spaces: the longest run of words naming
+    /// a role wins.
+    pub async fn role(&mut self, name: &'static str) -> anyhow::Result<Role> {
+        let Some(first) = self.tokens.front().map(|word| word.text) else {
+            return Err(ArgError::missing(name).into());
+        };
+
+        let expected = "a role in this server";
+        let Some(guild_id) = self.guild_id else {
+            return Err(ArgError::invalid(name, first, expected).into());
+        };
+        let roles = guild_roles(self.ctx, guild_id).await?;
+
+        let longest = if parse_role_id(first).is_some() {
+            1
+        } else {
+            self.tokens.len()
+        };
+
+        for count in (1..=longest).rev() {
+            let candidate = self
+                .tokens
+                .range(..count)
+                .map(|word| word.text)
+                .collect::<Vec<_>>()
+                .join(" ");
+            if let Some(role) = find_role(&roles, &candidate) {
+                self.tokens.drain(..count);
+                return Ok(role.clone());
+            }
+        }
+
+        Err(ArgError::invalid(name, first, expected).into())
+    }
+
+    /// A channel mention or ID in this server.
+    pub async fn channel(&mut self, name: &'static str) -> anyhow::Result<Channel> {
+        let raw = self.next_word(name)?;
+        let expected = "a channel in this server";
+        let Some(channel_id) = parse_channel_id(raw) else {
+            return Err(ArgError::invalid(name, raw, expected).into());
+        };
+
+        match fetch_channel(self.ctx, channel_id).await {
+            Ok(channel) if channel.guild_id.is_some() && channel.guild_id == self.guild_id => {
+                Ok(channel)
+            }
+            Ok(_) => Err(ArgError::invalid(name, raw, expected).into()),
+            Err(source) => Err(lookup_error(source, ArgError::invalid(name, raw, expected))),
+        }
+    }
+
+    /// A command name or built-in alias.
+    pub fn command(&mut self, name: &'static str) -> Result<&'static CommandMeta, ArgError> {
+        let raw = self.next_word(name)?;
+        find_command(&raw.to_ascii_lowercase(), &BTreeMap::new())
+            .ok_or_else(|| ArgError::invalid(name, raw, "a command name"))
+    }
+
+    /// A server ID.
+    pub fn guild(&mut self, name: &'static str) -> Result<Id<GuildMarker>, ArgError> {
+        let raw = self.next_word(name)?;
+        parse_guild_id(raw).ok_or_else(|| ArgError::invalid(name, raw, "a server ID"))
+    }
+
+    /// A duration like `30s`, `10m`, `2h` or `1d` of at most `max_secs`, in seconds.
+    pub fn duration(&mut self, name: &'static str, max_secs: u64) -> Result<u64, ArgError> {
+        let raw = self.next_word(name)?;
+        parse_duration_seconds(raw)
+            .filter(|secs| *secs <= max_secs)
+            .ok_or_else(|| {
+                let expected = format!(
+                    "a duration up to {} like `10m`, `2h` or `1d`",
+                    format_duration_seconds(max_secs)
+                );
+                ArgError::invalid(name, raw, expected)
+            })
+    }
+
+    /// A duration if the next word is one, otherwise `None`.
+    ///
+    /// Unlike [`Args::optional`], a duration longer than `max_secs` is an error
+    /// rather than being left for the next argument.
+    pub fn optional_duration(
+        &mut self,
+        name: &'static str,
+        max_secs: u64,
+    ) -> Result<Option<u64>, ArgError> {
+        let is_duration = self
+            .tokens
+            .front()
+            .is_some_and(|word| parse_duration_seconds(word.text).is_some());
+        if !is_duration {
+            return Ok(None);
+        }
+
+        self.duration(name, max_secs).map(Some)
+    }
+
+    /// A whole number within `range`.
+    pub fn integer<T>(
+        &mut self,
+        name: &'static str,
+        range: RangeInclusive<T>,
+    ) -> Result<T, ArgError>
+    where
+        T: FromStr + PartialOrd + fmt::Display,
+    {
+        let raw = self.next_word(name)?;
+        raw.parse::<T>()
+            .ok()
+            .filter(|value| range.contains(value))
+            .ok_or_else(|| {
+                let expected = format!("a whole number from {} to {}", range.start(), range.end());
+                ArgError::invalid(name, raw, expected)
+            })
+    }
+
+    /// A page number of a paginated listing, or 1 when nothing is left.
+    pub fn page(&mut self) -> Result<usize, ArgError> {
+        if self.is_empty() {
+            return Ok(1);
+        }
+
+        self.integer("page", 1..=MAX_PAGE)
+    }
+
+    /// One of a fixed set of words, matched case-insensitively.
+    pub fn choice(
+        &mut self,
+        name: &'static str,
+        choices: &'static [&'static str],
+    ) -> Result<&'static str, ArgError> {
+        let raw = self.next_word(name)?;
+        choices
+            .iter()
+            .find(|choice| choice.eq_ignore_ascii_case(raw))
+            .copied()
+            .ok_or_else(|| {
+                let listed = choices
+                    .iter()
+                    .map(|choice| format!("`{}`", choice))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ArgError::invalid(name, raw, format!("one of {}", listed))
+            })
+    }
+
+    /// Everything left, exactly as typed, quotes and spacing included.
+    ///
+    /// A leading `--` is dropped, so text starting with a dash can follow it.
+    /// When all that is left is one quoted word, its quotes are dropped too.
+    pub fn rest(&mut self, name: &'static str) -> Result<String, ArgError> {
+        if self.tokens.front().is_some_and(|word| word.text == "--") {
+            self.tokens.pop_front();
+        }
+
+        let rest = remaining_text(self.raw, &self.tokens).ok_or_else(|| ArgError::missing(name))?;
+        self.tokens.clear();
+        Ok(rest)
+    }
+
+    /// Try an argument, leaving the input untouched if it is absent or doesn't parse.
+    pub fn optional<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ArgError>,
+    ) -> Option<T> {
+        let before = self.tokens.clone();
+        match parse(self) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                self.tokens = before;
+                None
+            }
+        }
+    }
+
+    /// One or more of the same argument, up to the end of the input.
+    pub fn variadic<T>(
+        &mut self,
+        mut parse: impl FnMut(&mut Self) -> Result<T, ArgError>,
+    ) -> Result<Vec<T>, ArgError> {
+        let mut values = vec![parse(self)?];
+        while !self.is_empty() {
+            values.push(parse(self)?);
+        }
+
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(input: &str) -> Vec<String> {
+        tokenize(input)
+            .into_iter()
+            .map(|token| token.text)
+            .collect()
+    }
+
+    #[test]
+    fn splits_on_any_whitespace() {
+        assert_eq!(
+            texts("  ban  @u\tspam\nlinks "),
+            ["ban", "@u", "spam", "links"]
+        );
+        assert!(texts("   ").is_empty());
+    }
+
+    #[test]
+    fn quotes_group_words() {
+        assert_eq!(
+            texts(r#"@u "spam  links" end"#),
+            ["@u", "spam  links", "end"]
+        );
+        assert_eq!(texts(r#"a"b c"d"#), ["ab cd"]);
+    }
+
+    #[test]
+    fn empty_quotes_are_an_empty_token() {
+        assert_eq!(texts(r#"set "" x"#), ["set", "", "x"]);
+    }
+
+    #[test]
+    fn unclosed_quote_runs_to_the_end() {
+        assert_eq!(texts(r#"a "b c  d"#), ["a", "b c  d"]);
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(texts(r#"say \"hi\""#), ["say", r#""hi""#]);
+        assert_eq!(texts(r"a\\ b"), [r"a\", "b"]);
+        assert_eq!(texts(r"a\ b"), ["a b"]);
+        assert_eq!(texts(r#""quoted \" inside""#), [r#"quoted " inside"#]);
+    }
+
+    #[test]
+    fn other_backslashes_are_literal() {
+        assert_eq!(texts(r"C:\path \n"), [r"C:\path", r"\n"]);
+        assert_eq!(texts(r"trailing\"), [r"trailing\"]);
+    }
+
+    #[test]
+    fn tokens_record_where_they_start() {
+        let starts: Vec<usize> = tokenize(r#"é  "x y" \"z"#)
+            .iter()
+            .map(|token| token.start)
+            .collect();
+        assert_eq!(starts, [0, 4, 10]);
+    }
+
+    #[test]
+    fn arg_tail_keeps_the_input_as_typed() {
+        let args = CommandArgs::new(r#"  user  "quoted  reason"  "#);
+        assert_eq!(args.arg1(), Some("user"));
+        assert_eq!(args.arg_tail(), Some(r#""quoted  reason""#));
+    }
+
+    fn rest(input: &str) -> Option<String> {
+        let tokens = tokenize(input);
+        let words = tokens
+            .iter()
+            .map(|token| Word {
+                text: &token.text,
+                start: token.start,
+            })
+            .collect();
+        remaining_text(input, &words)
+    }
+
+    #[test]
+    fn rest_unquotes_a_single_quoted_word() {
+        assert_eq!(rest(r#""spam  links""#).as_deref(), Some("spam  links"));
+        assert_eq!(rest(r#""spam" links"#).as_deref(), Some(r#""spam" links"#));
+        assert_eq!(rest("spam links").as_deref(), Some("spam links"));
+        assert_eq!(rest("").as_deref(), None);
+    }
+}
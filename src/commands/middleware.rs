@@ -0,0 +1,396 @@
+//! Checks and hooks wrapped around every message command.
+//!
+//! Each command's [`CommandMeta`] declares what applies to it (`owner_only`,
+//! `guild_only`, `permissions`, `cooldown`); [`run`] walks [`CHAIN`] before and after the
+//! command so individual `run` functions don't repeat those checks.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use tracing::{debug, error, warn};
+use twilight_model::{
+    gateway::payload::incoming::MessageCreate,
+    id::{Id, marker::GuildMarker},
+};
+
+use crate::commands::args::{ArgError, CommandArgs};
+use crate::commands::registry::Command;
+use crate::commands::{CommandMeta, CooldownBucket, admin};
+use crate::context::Context;
+use crate::database::command_stats::record_run;
+use crate::database::cooldowns::{CooldownState, hit_cooldown, release_cooldown};
+use crate::error::BotError;
+use crate::util::parse::format_duration_seconds;
+use crate::util::permissions::has_command_permission;
+
+/// Boxed future returned by [`Middleware`] hooks.
+pub type MiddlewareFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// One message command about to run, as seen by middleware.
+pub struct Invocation<'a> {
+    pub ctx: &'a Context,
+    pub msg: &'a MessageCreate,
+    /// The top-level command.
+    pub command: &'static CommandMeta,
+    /// Subcommands resolved from the arguments, outermost first.
+    pub subcommands: Vec<&'static CommandMeta>,
+    /// The deepest command matched, which supplies the cooldown and usage.
+    pub invoked: &'static CommandMeta,
+    /// E.g. `warnings clear`.
+    pub name: String,
+    pub prefix: &'a str,
+}
+
+impl Invocation<'_> {
+    /// The command followed by its resolved subcommands.
+    fn path(&self) -> impl Iterator<Item = &'static CommandMeta> + '_ {
+        std::iter::once(self.command).chain(self.subcommands.iter().copied())
+    }
+
+    async fn reply(&self, content: &str) -> anyhow::Result<()> {
+        self.ctx
+            .http
+            .create_message(self.msg.channel_id)
+            .content(content)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// What came of running a command.
+pub struct Outcome {
+    pub result: anyhow::Result<()>,
+    pub elapsed: Duration,
+}
+
+/// A step of the command chain.
+pub trait Middleware: Send + Sync {
+    /// Runs before the command. Resolves to `false` to stop the chain, after
+    /// replying if the user should know why.
+    fn before<'a>(&'a self, _invocation: &'a Invocation<'a>) -> MiddlewareFuture<'a, bool> {
+        Box::pin(async { Ok(true) })
+    }
+
+    /// Runs after the command, only if every check passed.
+    fn after<'a>(
+        &'a self,
+        _invocation: &'a Invocation<'a>,
+        _outcome: &'a Outcome,
+    ) -> MiddlewareFuture<'a, ()> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Every middleware, in order. Checks run top to bottom, and so do the hooks after.
+const CHAIN: &[&dyn Middleware] = &[
+    &OwnerOnly,
+    &GuildOnly,
+    &Policy,
+    &RequiredPermissions,
+    &Cooldown,
+    &Logging,
+    &Metrics,
+    &ErrorReply,
+    // Add new middleware here
+];
+
+/// Run a command through the chain: checks, the command itself, then hooks.
+pub async fn run(
+    invocation: &Invocation<'_>,
+    command: &'static dyn Command,
+    args: CommandArgs,
+) -> anyhow::Result<()> {
+    for middleware in CHAIN {
+        if !middleware.before(invocation).await? {
+            return Ok(());
+        }
+    }
+
+    let started = Instant::now();
+    let msg = Box::new(invocation.msg.clone());
+    let result = command.run(invocation.ctx.clone(), msg, args).await;
+    let outcome = Outcome {
+        result,
+        elapsed: started.elapsed(),
+    };
+
+    for middleware in CHAIN {
+        middleware.after(invocation, &outcome).await?;
+    }
+
+    Ok(())
+}
+
+/// Guild of a message that ran a `guild_only` command.
+///
+/// [`GuildOnly`] refuses direct messages first, so this only fails when a
+/// command uses it without declaring `guild_only`.
+pub fn message_guild_id(msg: &MessageCreate) -> anyhow::Result<Id<GuildMarker>> {
+    msg.guild_id
+        .context("command isn't declared guild_only but needs a server")
+}
+
+/// Ignore anyone but the bot owners for commands declared `owner_only`.
+///
+/// There is no reply, so guessing the name of a hidden owner command doesn't
+/// reveal that it exists.
+struct OwnerOnly;
+
+impl Middleware for OwnerOnly {
+    fn before<'a>(&'a self, invocation: &'a Invocation<'a>) -> MiddlewareFuture<'a, bool> {
+        Box::pin(async move {
+            Ok(!invocation.path().any(|meta| meta.owner_only)
+                || invocation
+                    .ctx
+                    .config
+                    .current()
+                    .is_owner(invocation.msg.author.id))
+        })
+    }
+}
+
+/// Refuse commands declared `guild_only` in direct messages.
+struct GuildOnly;
+
+impl Middleware for GuildOnly {
+    fn before<'a>(&'a self, invocation: &'a Invocation<'a>) -> MiddlewareFuture<'a, bool> {
+        Box::pin(async move {
+            if invocation.msg.guild_id.is_some() || !invocation.path().any(|meta| meta.guild_only) {
+                return Ok(true);
+            }
+
+            invocation
+                .reply("This command only works in servers.")
+                .await?;
+            Ok(false)
+        })
+    }
+}
+
+/// Apply the guild's `!command` policy.
+struct Policy;
+
+impl Middleware for Policy {
+    fn before<'a>(&'a self, invocation: &'a Invocation<'a>) -> MiddlewareFuture<'a, bool> {
+        Box::pin(admin::command::check_policy(
+            invocation.ctx,
+            invocation.msg,
+            invocation.command,
+            invocation.prefix,
+        ))
+    }
+}
+
+/// Require the permissions declared by the command and each resolved subcommand.
+///
+/// Roles granted access to the command through `!access` pass for all of its
+/// subcommands too.
+struct RequiredPermissions;
+
+impl Middleware for RequiredPermissions {
+    fn before<'a>(&'a self, invocation: &'a Invocation<'a>) -> MiddlewareFuture<'a, bool> {
+        Box::pin(async move {
+            for meta in invocation.path() {
+                if meta.permissions.is_empty()
+                    || has_command_permission(
+                        invocation.ctx,
+                        invocation.msg,
+                        invocation.command.name,
+                        meta.permissions,
+                    )
+                    .await?
+                {
+                    continue;
+                }
+
+                invocation
+                    .reply("You are not permitted to use this command.")
+                    .await?;
+                return Ok(false);
+            }
+
+            Ok(true)
+        })
+    }
+}
+
+/// Refuse a command that is still cooling down for this user, channel or guild.
+///
+/// Runs after the permission checks so members who can't use a command can't
+/// start its cooldown, and a use rejected for bad arguments is refunded. Only
+/// the first refused use gets a reply so spamming a command can't make the bot
+/// spam back.
+struct Cooldown;
+
+impl Cooldown {
+    fn bucket_id(invocation: &Invocation<'_>, bucket: CooldownBucket) -> u64 {
+        let msg = invocation.msg;
+        match bucket {
+            CooldownBucket::User => msg.author.id.get(),
+            CooldownBucket::Channel => msg.channel_id.get(),
+            CooldownBucket::Guild => msg.guild_id.map_or(msg.channel_id.get(), |id| id.get()),
+        }
+    }
+}
+
+impl Middleware for Cooldown {
+    fn before<'a>(&'a self, invocation: &'a Invocation<'a>) -> MiddlewareFuture<'a, bool> {
+        Box::pin(async move {
+            let Some(cooldown) = invocation.invoked.cooldown else {
+                return Ok(true);
+            };
+
+            let bucket_id = Self::bucket_id(invocation, cooldown.bucket);
+            let length = Duration::from_secs(cooldown.secs);
+            let CooldownState::Waiting {
+                remaining,
+                first_refusal,
+            } = hit_cooldown(&invocation.name, bucket_id, length).await
+            else {
+                return Ok(true);
+            };
+
+            if first_refusal {
+                let wait_secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+                let out = format!(
+                    "`{}{}` is on cooldown. Try again in {}.",
+                    invocation.prefix,
+                    invocation.name,
+                    format_duration_seconds(wait_secs)
+                );
+                invocation.reply(&out).await?;
+            }
+
+            Ok(false)
+        })
+    }
+
+    fn after<'a>(
+        &'a self,
+        invocation: &'a Invocation<'a>,
+        outcome: &'a Outcome,
+    ) -> MiddlewareFuture<'a, ()> {
+        Box::pin(async move {
+            if let Some(cooldown) = invocation.invoked.cooldown
+                && outcome
+                    .result
+                    .as_ref()
+                    .is_err_and(|source| source.is::<ArgError>())
+            {
+                let bucket_id = Self::bucket_id(invocation, cooldown.bucket);
+                release_cooldown(&invocation.name, bucket_id).await;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Log every run; failures are logged with their full error chain.
+///
+/// Discord refusals the user can fix (permissions, unknown targets, limits)
+/// are warnings rather than errors.
+struct Logging;
+
+impl Middleware for Logging {
+    fn after<'a>(
+        &'a self,
+        invocation: &'a Invocation<'a>,
+        outcome: &'a Outcome,
+    ) -> MiddlewareFuture<'a, ()> {
+        Box::pin(async move {
+            let command = invocation.name.as_str();
+            let user_id = invocation.msg.author.id.get();
+            let guild_id = invocation.msg.guild_id.map(|id| id.get());
+            let elapsed_ms = outcome.elapsed.as_millis();
+
+            match &outcome.result {
+                Ok(()) => debug!(command, user_id, guild_id, elapsed_ms, "command ran"),
+                Err(source) if source.is::<ArgError>() => {
+                    debug!(command, user_id, guild_id, %source, "command got invalid arguments");
+                }
+                Err(source) if BotError::chain_is_expected(source) => {
+                    warn!(
+                        command,
+                        user_id,
+                        guild_id,
+                        elapsed_ms,
+                        ?source,
+                        "command refused by discord"
+                    );
+                }
+                Err(source) => {
+                    error!(
+                        command,
+                        user_id,
+                        guild_id,
+                        elapsed_ms,
+                        ?source,
+                        "command failed"
+                    );
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Count runs, failures and time spent per command for `!status`.
+struct Metrics;
+
+impl Middleware for Metrics {
+    fn after<'a>(
+        &'a self,
+        invocation: &'a Invocation<'a>,
+        outcome: &'a Outcome,
+    ) -> MiddlewareFuture<'a, ()> {
+        Box::pin(async move {
+            let failed = outcome
+                .result
+                .as_ref()
+                .is_err_and(|source| !source.is::<ArgError>());
+            record_run(&invocation.name, outcome.elapsed, failed).await;
+
+            Ok(())
+        })
+    }
+}
+
+/// Tell the user why a command failed.
+///
+/// Argument errors name the bad argument and show the usage, and Discord
+/// failures say what Discord refused; anything else gets a generic reply,
+/// since the details were already logged.
+struct ErrorReply;
+
+impl Middleware for ErrorReply {
+    fn after<'a>(
+        &'a self,
+        invocation: &'a Invocation<'a>,
+        outcome: &'a Outcome,
+    ) -> MiddlewareFuture<'a, ()> {
+        Box::pin(async move {
+            let Err(source) = &outcome.result else {
+                return Ok(());
+            };
+
+            let out = if let Some(arg_error) = source.downcast_ref::<ArgError>() {
+                arg_error.reply(&invocation.invoked.usage_with_prefix(invocation.prefix))
+            } else if let Some(message) = BotError::chain_message(source) {
+                message
+            } else {
+                format!(
+                    "Something went wrong while running `{}{}`.",
+                    invocation.prefix, invocation.name
+                )
+            };
+            invocation.reply(&out).await
+        })
+    }
+}
@@ -1,175 +1,298 @@
+pub mod admin;
+pub mod args;
+pub mod middleware;
 pub mod moderation;
+pub mod owner;
+pub mod registry;
 pub mod utility;
 
+use std::{collections::BTreeMap, time::Duration};
+
 use twilight_model::{
     application::interaction::InteractionData,
     gateway::payload::incoming::{InteractionCreate, MessageCreate},
+    guild::Permissions,
+    id::{
+        Id,
+        marker::{GuildMarker, UserMarker},
+    },
 };
 
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::Invocation;
+use crate::commands::registry::{InteractionKind, registry};
 use crate::context::Context;
-use crate::util::COMMAND_PREFIX;
-
-#[derive(Clone, Copy)]
-enum InteractionRoute {
-    PermissionsButtons,
-    HelpButtons,
-    PagetestButtons,
-    PermissionsModal,
-    HelpModal,
-    PagetestModal,
-}
-
-fn route_interaction(custom_id: &str) -> Option<InteractionRoute> {
-    const ROUTES: [(&str, InteractionRoute); 6] = [
-        ("pg:permissions:", InteractionRoute::PermissionsButtons),
-        ("pg:help", InteractionRoute::HelpButtons),
-        ("pg:pagetest:", InteractionRoute::PagetestButtons),
-        ("pgm:permissions:", InteractionRoute::PermissionsModal),
-        ("pgm:help", InteractionRoute::HelpModal),
-        ("pgm:pagetest:", InteractionRoute::PagetestModal),
-    ];
-
-    ROUTES
-        .into_iter()
-        .find_map(|(prefix, route)| custom_id.starts_with(prefix).then_some(route))
-}
+use crate::database::aliases::guild_aliases;
+use crate::database::blacklist::{BlacklistKind, is_blacklisted};
+use crate::database::cooldowns::{CooldownState, hit_cooldown};
+use crate::database::guild_config::guild_config;
+use crate::util::parse::format_duration_seconds;
 
 // Global command meta data
+#[derive(Debug)]
 pub struct CommandMeta {
     pub name: &'static str,
     pub desc: &'static str,
     pub category: &'static str,
     pub usage: &'static str,
+    /// Built-in alternative names, available in every guild.
+    pub aliases: &'static [&'static str],
+    pub cooldown: Option<Cooldown>,
+    /// Refuse to run in direct messages.
+    pub guild_only: bool,
+    /// Only run for configured bot owners; anyone else is ignored without a reply.
+    pub owner_only: bool,
+    /// Discord permissions required to run, unless `!access` grants the command.
+    pub permissions: Permissions,
+    /// Nested commands like `!warnings clear`, matched by name or alias.
+    pub subcommands: &'static [CommandMeta],
+}
+
+/// Who shares a command's cooldown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CooldownBucket {
+    User,
+    Channel,
+    /// The whole guild, or the channel in direct messages.
+    Guild,
+}
+
+/// How often a command may be used, enforced before it runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cooldown {
+    pub secs: u64,
+    pub bucket: CooldownBucket,
+}
+
+impl Cooldown {
+    pub const fn per_user(secs: u64) -> Self {
+        Self {
+            secs,
+            bucket: CooldownBucket::User,
+        }
+    }
+
+    pub const fn per_channel(secs: u64) -> Self {
+        Self {
+            secs,
+            bucket: CooldownBucket::Channel,
+        }
+    }
+
+    pub const fn per_guild(secs: u64) -> Self {
+        Self {
+            secs,
+            bucket: CooldownBucket::Guild,
+        }
+    }
+
+    /// E.g. "5s per user".
+    pub fn describe(&self) -> String {
+        let bucket = match self.bucket {
+            CooldownBucket::User => "user",
+            CooldownBucket::Channel => "channel",
+            CooldownBucket::Guild => "server",
+        };
+
+        format!("{} per {}", format_duration_seconds(self.secs), bucket)
+    }
+}
+
+impl CommandMeta {
+    /// Usage syntax with the default `!` replaced by a guild's prefix.
+    pub fn usage_with_prefix(&self, prefix: &str) -> String {
+        let mut out = String::with_capacity(self.usage.len());
+        let mut at_word_start = true;
+
+        for ch in self.usage.chars() {
+            if ch == '!' && at_word_start {
+                out.push_str(prefix);
+            } else {
+                out.push(ch);
+            }
+            at_word_start = ch.is_whitespace();
+        }
+
+        out
+    }
+
+    /// "Usage: …" reply line with a guild's prefix.
+    pub fn usage_line(&self, prefix: &str) -> String {
+        format!("Usage: `{}`", self.usage_with_prefix(prefix))
+    }
+
+    /// A direct subcommand by name or alias (case-insensitive).
+    pub fn subcommand(&self, name: &str) -> Option<&'static CommandMeta> {
+        self.subcommands.iter().find(|sub| {
+            sub.name.eq_ignore_ascii_case(name)
+                || sub
+                    .aliases
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// Built-in aliases followed by any custom aliases a guild points at this command.
+    pub fn all_aliases<'a>(&'a self, guild_aliases: &'a BTreeMap<String, String>) -> Vec<&'a str> {
+        let custom = guild_aliases
+            .iter()
+            .filter(|(_, command)| command.as_str() == self.name)
+            .map(|(alias, _)| alias.as_str());
+
+        self.aliases.iter().copied().chain(custom).collect()
+    }
 }
 
-pub const COMMANDS: &[CommandMeta] = &[
-    utility::ping::META,
-    utility::universe::META,
-    utility::help::META,
-    utility::usage::META,
-    utility::pagetest::META,
-    moderation::ban::META,
-    moderation::unban::META,
-    moderation::kick::META,
-    moderation::timeout::META,
-    moderation::untimeout::META,
-    moderation::warn::META,
-    moderation::warnings::META,
-    moderation::purge::META,
-    moderation::permissions::META,
-    // Add new commands here
-];
+/// Metadata of a command by name, built-in alias or custom guild alias.
+pub fn find_command(
+    name: &str,
+    guild_aliases: &BTreeMap<String, String>,
+) -> Option<&'static CommandMeta> {
+    registry()
+        .find(name, guild_aliases)
+        .map(|command| command.meta())
+}
+
+/// Shortest gap between two "Did you mean" replies to the same user.
+const SUGGESTION_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// "Did you mean …?" for a mistyped command, if any command is close enough.
+pub fn did_you_mean(
+    typed: &str,
+    guild_aliases: &BTreeMap<String, String>,
+    prefix: &str,
+    show_owner: bool,
+) -> Option<String> {
+    let mut names: Vec<String> = registry()
+        .suggest(typed, guild_aliases, show_owner)
+        .into_iter()
+        .map(|name| format!("`{}{}`", prefix, name))
+        .collect();
+
+    let last = names.pop()?;
+    if names.is_empty() {
+        return Some(format!("Did you mean {}?", last));
+    }
+
+    Some(format!("Did you mean {} or {}?", names.join(", "), last))
+}
 
 pub async fn handle_message(ctx: Context, msg: Box<MessageCreate>) -> anyhow::Result<()> {
-    if msg.author.bot {
+    if msg.author.bot || is_blacklisted_source(Some(msg.author.id), msg.guild_id).await {
         return Ok(());
     }
 
     let content_owned = msg.content.clone();
     let content = content_owned.trim();
 
-    if !content.starts_with(COMMAND_PREFIX) {
+    let config = guild_config(msg.guild_id.map(|id| id.get())).await;
+    let Some(content) = strip_command_prefix(content, &config.prefix, ctx.bot_user_id) else {
         return Ok(());
-    }
+    };
 
-    let content = content.trim_start_matches(COMMAND_PREFIX).trim();
+    let content = content.trim();
     let mut command_and_rest = content.splitn(2, char::is_whitespace);
     let cmd = command_and_rest.next().unwrap_or("").to_ascii_lowercase();
-    let rest = command_and_rest
-        .next()
-        .map(str::trim)
-        .filter(|value| !value.is_empty());
-
-    let (arg1, arg_tail): (Option<String>, Option<String>) = match rest {
-        Some(value) => {
-            let mut args = value.splitn(2, char::is_whitespace);
-            let first = args
-                .next()
-                .filter(|arg| !arg.is_empty())
-                .map(ToOwned::to_owned);
-            let tail = args
-                .next()
-                .map(str::trim)
-                .filter(|remaining| !remaining.is_empty())
-                .map(ToOwned::to_owned);
-
-            (first, tail)
+    let mut args = CommandArgs::new(command_and_rest.next().unwrap_or(""));
+
+    let aliases = guild_aliases(msg.guild_id.map(|id| id.get())).await;
+    let Some(command) = registry().find(&cmd, &aliases) else {
+        let show_owner = ctx.config.current().is_owner(msg.author.id);
+        if config.command_suggestions
+            && let Some(out) = did_you_mean(&cmd, &aliases, &config.prefix, show_owner)
+            && hit_cooldown("did-you-mean", msg.author.id.get(), SUGGESTION_COOLDOWN).await
+                == CooldownState::Ready
+        {
+            ctx.http
+                .create_message(msg.channel_id)
+                .content(&out)
+                .await?;
         }
-        None => (None, None),
+        return Ok(());
+    };
+
+    let invoked = args.resolve_subcommands(command.meta());
+    let invocation = Invocation {
+        ctx: &ctx,
+        msg: &msg,
+        command: command.meta(),
+        subcommands: args.subcommands().to_vec(),
+        invoked,
+        name: qualified_name(command.meta(), &args.subcommand_path()),
+        prefix: &config.prefix,
     };
 
-    let arg1 = arg1.as_deref();
-    let arg_tail = arg_tail.as_deref();
-
-    match cmd.as_str() {
-        "ping" => utility::ping::run(ctx.clone(), msg).await?,
-        "universe" => utility::universe::run(ctx.clone(), msg).await?,
-        "help" => utility::help::run(ctx.clone(), msg, arg1).await?,
-        "usage" => utility::usage::run(ctx.clone(), msg, arg1).await?,
-        "pagetest" => utility::pagetest::run(ctx.clone(), msg, arg1).await?,
-
-        "ban" => moderation::ban::run(ctx.clone(), msg, arg1, arg_tail).await?,
-        "unban" => moderation::unban::run(ctx.clone(), msg, arg1, arg_tail).await?,
-        "kick" => moderation::kick::run(ctx.clone(), msg, arg1, arg_tail).await?,
-        "timeout" => moderation::timeout::run(ctx.clone(), msg, arg1, arg_tail).await?,
-        "untimeout" => moderation::untimeout::run(ctx.clone(), msg, arg1, arg_tail).await?,
-        "warn" => moderation::warn::run(ctx.clone(), msg, arg1, arg_tail).await?,
-        "warnings" => moderation::warnings::run(ctx.clone(), msg, arg1, arg_tail).await?,
-        "permissions" => moderation::permissions::run(ctx.clone(), msg, arg1).await?,
-        "purge" => moderation::purge::run(ctx.clone(), msg, arg1).await?,
-        // Add new commands here
-        _ => {}
+    middleware::run(&invocation, command, args).await
+}
+
+/// A command name followed by its subcommand path, e.g. `warnings clear`.
+pub fn qualified_name(command: &CommandMeta, path: &[&str]) -> String {
+    std::iter::once(command.name)
+        .chain(path.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether a user or the guild they are acting in is globally blacklisted.
+///
+/// Blacklisted sources are ignored without a reply.
+async fn is_blacklisted_source(
+    user_id: Option<Id<UserMarker>>,
+    guild_id: Option<Id<GuildMarker>>,
+) -> bool {
+    if let Some(user_id) = user_id
+        && is_blacklisted(BlacklistKind::User, user_id.get()).await
+    {
+        return true;
     }
 
-    Ok(())
+    match guild_id {
+        Some(guild_id) => is_blacklisted(BlacklistKind::Guild, guild_id.get()).await,
+        None => false,
+    }
+}
+
+/// Strip the guild's prefix, or a leading mention of the bot, from a message.
+///
+/// Mentioning the bot always works so a forgotten prefix can be recovered.
+fn strip_command_prefix<'a>(
+    content: &'a str,
+    prefix: &str,
+    bot_user_id: Id<UserMarker>,
+) -> Option<&'a str> {
+    if let Some(rest) = content.strip_prefix(prefix) {
+        return Some(rest);
+    }
+
+    let mention = content.strip_prefix("<@")?;
+    let mention = mention.strip_prefix('!').unwrap_or(mention);
+    mention
+        .strip_prefix(bot_user_id.get().to_string().as_str())?
+        .strip_prefix('>')
 }
 
 pub async fn handle_interaction(
     ctx: Context,
     interaction: Box<InteractionCreate>,
 ) -> anyhow::Result<()> {
+    if is_blacklisted_source(interaction.author_id(), interaction.guild_id).await {
+        return Ok(());
+    }
+
     let custom_id = match interaction.data.as_ref() {
         Some(InteractionData::MessageComponent(data)) => data.custom_id.clone(),
         Some(InteractionData::ModalSubmit(data)) => data.custom_id.clone(),
         _ => return Ok(()),
     };
 
-    let Some(route) = route_interaction(&custom_id) else {
+    let Some((command, kind)) = registry().route_interaction(&custom_id) else {
         return Ok(());
     };
 
-    match route {
-        InteractionRoute::PermissionsButtons => {
-            let _handled =
-                moderation::permissions::handle_pagination_interaction(ctx.clone(), interaction)
-                    .await?;
-        }
-        InteractionRoute::HelpButtons => {
-            let _handled =
-                utility::help::handle_pagination_interaction(ctx.clone(), interaction).await?;
-        }
-        InteractionRoute::PagetestButtons => {
-            let _handled =
-                utility::pagetest::handle_pagination_interaction(ctx.clone(), interaction).await?;
-        }
-        InteractionRoute::PermissionsModal => {
-            let _handled = moderation::permissions::handle_pagination_modal_interaction(
-                ctx.clone(),
-                interaction,
-            )
-            .await?;
-        }
-        InteractionRoute::HelpModal => {
-            let _handled =
-                utility::help::handle_pagination_modal_interaction(ctx.clone(), interaction)
-                    .await?;
-        }
-        InteractionRoute::PagetestModal => {
-            let _handled =
-                utility::pagetest::handle_pagination_modal_interaction(ctx.clone(), interaction)
-                    .await?;
-        }
-    }
+    let _handled = match kind {
+        InteractionKind::Component => command.handle_component(ctx, interaction).await?,
+        InteractionKind::Modal => command.handle_modal(ctx, interaction).await?,
+    };
 
     Ok(())
 }
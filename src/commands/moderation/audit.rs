@@ -0,0 +1,446 @@
+use std::sync::Arc;
+
+use tracing::debug;
+use twilight_model::{
+    gateway::payload::incoming::{InteractionCreate, MessageCreate},
+    guild::{Permissions, Role},
+    id::{
+        Id,
+        marker::{GuildMarker, UserMarker},
+    },
+};
+
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
+use crate::commands::moderation::embeds::guild_only_message;
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown};
+use crate::context::Context;
+use crate::database::guild_config::guild_config;
+use crate::util::pagination::{
+    PaginationInteractionValidation, PaginationModalSubmitValidation, build_paginated_list_view,
+    clamp_page, open_jump_modal_from_token, resolve_modal_target_page, respond_ephemeral_message,
+    send_paginated_message, timeout_secs, total_pages, update_paginated_interaction_message,
+    validate_interaction_for_command_prefix, validate_jump_modal_for_command_prefix,
+};
+use crate::util::permissions::{guild_roles, permission_names};
+
+pub const META: CommandMeta = CommandMeta {
+    name: "audit",
+    desc: "Flag roles carrying dangerous permissions.",
+    category: "moderation",
+    usage: "!audit roles [page]",
+    aliases: &[],
+    cooldown: Some(Cooldown::per_user(5)),
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MANAGE_GUILD,
+    subcommands: &[],
+};
+
+command!(AuditCommand => run, paginated);
+
+const ROLES_PER_PAGE: usize = 5;
+
+const AUDIT_TITLE: &str = "Role Permission Audit";
+
+/// Pagination command of an audit built from a partial member list.
+const PARTIAL_AUDIT_COMMAND: &str = "audit|partial";
+
+/// Members listed per flagged role before collapsing into a count.
+const MEMBERS_SHOWN_PER_ROLE: usize = 10;
+
+/// Members requested per HTTP page, the most Discord allows.
+const MEMBER_FETCH_LIMIT: u16 = 1000;
+
+/// Pages fetched before giving up on listing every member.
+const MEMBER_FETCH_PAGES: usize = 25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Medium => "🟡 Medium",
+            Severity::High => "🟠 High",
+            Severity::Critical => "🔴 Critical",
+        }
+    }
+}
+
+/// Permissions worth flagging, with how much damage a compromised holder could do.
+const RISKY_PERMISSIONS: &[(Permissions, Severity)] = &[
+    (Permissions::ADMINISTRATOR, Severity::Critical),
+    (Permissions::MANAGE_GUILD, Severity::High),
+    (Permissions::MANAGE_ROLES, Severity::High),
+    (Permissions::BAN_MEMBERS, Severity::High),
+    (Permissions::MANAGE_WEBHOOKS, Severity::High),
+    (Permissions::MANAGE_CHANNELS, Severity::High),
+    (Permissions::KICK_MEMBERS, Severity::Medium),
+    (Permissions::MODERATE_MEMBERS, Severity::Medium),
+    (Permissions::MANAGE_MESSAGES, Severity::Medium),
+    (Permissions::MENTION_EVERYONE, Severity::Medium),
+];
+
+/// Audit every guild role for dangerous permissions.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
+
+    let mut args = args.parser(&ctx, &msg);
+    args.choice("scope", &["roles"])?;
+    let requested_page = args.page()?;
+
+    let members_complete = refresh_member_cache(&ctx, guild_id).await;
+    let audit = build_audit(&ctx, guild_id, members_complete).await?;
+    let items = audit.items;
+    if items.is_empty() {
+        http.create_message(msg.channel_id)
+            .content("No roles carry dangerous permissions.")
+            .await?;
+        return Ok(());
+    }
+
+    let total = total_pages(items.len(), ROLES_PER_PAGE);
+    if requested_page > total {
+        let out = format!(
+            "Page {} does not exist. Available pages: 1-{}.",
+            requested_page, total
+        );
+        http.create_message(msg.channel_id).content(&out).await?;
+        return Ok(());
+    }
+
+    let embed_color = guild_config(Some(guild_id.get())).await.embed_color;
+    let (embed, components) = build_paginated_list_view(
+        audit.command,
+        audit.title,
+        embed_color,
+        &items,
+        requested_page,
+        ROLES_PER_PAGE,
+        msg.author.id.get(),
+        timeout_secs(),
+    )?;
+
+    send_paginated_message(
+        Arc::clone(&ctx.http),
+        msg.channel_id,
+        embed,
+        components,
+        total,
+        timeout_secs(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Handle pagination button presses for the `audit` command.
+pub async fn handle_pagination_interaction(
+    ctx: Context,
+    interaction: Box<InteractionCreate>,
+) -> anyhow::Result<bool> {
+    let http = &ctx.http;
+    let (actor_id, token) =
+        match validate_interaction_for_command_prefix(http, &interaction, "audit").await? {
+            PaginationInteractionValidation::NotForCommand => return Ok(false),
+            PaginationInteractionValidation::HandledInvalid => return Ok(true),
+            PaginationInteractionValidation::Valid {
+                actor_user_id,
+                token,
+            } => (actor_user_id, token),
+        };
+
+    let Some(audit) = interaction_audit(&ctx, &interaction, &token.command).await? else {
+        return Ok(true);
+    };
+    let items = audit.items;
+
+    let total = total_pages(items.len(), ROLES_PER_PAGE);
+
+    if token.action == "jump" {
+        open_jump_modal_from_token(http, &interaction, &token, total).await?;
+        return Ok(true);
+    }
+
+    let target_page = clamp_page(token.page, total);
+    let embed_color = guild_config(interaction.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
+    let (embed, components) = build_paginated_list_view(
+        audit.command,
+        audit.title,
+        embed_color,
+        &items,
+        target_page,
+        ROLES_PER_PAGE,
+        actor_id,
+        timeout_secs(),
+    )?;
+
+    update_paginated_interaction_message(
+        Arc::clone(&ctx.http),
+        &interaction,
+        embed,
+        components,
+        total,
+        timeout_secs(),
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// Handle jump-modal submit interactions for the `audit` command.
+pub async fn handle_pagination_modal_interaction(
+    ctx: Context,
+    interaction: Box<InteractionCreate>,
+) -> anyhow::Result<bool> {
+    let http = &ctx.http;
+    let (actor_id, command, entered_page, total_pages_hint) =
+        match validate_jump_modal_for_command_prefix(http, &interaction, "audit").await? {
+            PaginationModalSubmitValidation::NotForCommand => return Ok(false),
+            PaginationModalSubmitValidation::HandledInvalid => return Ok(true),
+            PaginationModalSubmitValidation::Valid {
+                actor_user_id,
+                command,
+                requested_page,
+                total_pages_hint,
+            } => (actor_user_id, command, requested_page, total_pages_hint),
+        };
+
+    let Some(audit) = interaction_audit(&ctx, &interaction, &command).await? else {
+        return Ok(true);
+    };
+    let items = audit.items;
+
+    let total = total_pages(items.len(), ROLES_PER_PAGE);
+    let target_page = resolve_modal_target_page(entered_page, total, total_pages_hint);
+
+    let embed_color = guild_config(interaction.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
+    let (embed, components) = build_paginated_list_view(
+        audit.command,
+        audit.title,
+        embed_color,
+        &items,
+        target_page,
+        ROLES_PER_PAGE,
+        actor_id,
+        timeout_secs(),
+    )?;
+
+    update_paginated_interaction_message(
+        Arc::clone(&ctx.http),
+        &interaction,
+        embed,
+        components,
+        total,
+        timeout_secs(),
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// Rebuild the audit for a page change from cached members only.
+///
+/// The member list was fetched when `!audit` ran, so page presses don't
+/// repeat it; whether it was complete is kept in the pagination `command`.
+async fn interaction_audit(
+    ctx: &Context,
+    interaction: &InteractionCreate,
+    command: &str,
+) -> anyhow::Result<Option<Audit>> {
+    let Some(guild_id) = interaction.guild_id else {
+        respond_ephemeral_message(&ctx.http, interaction, guild_only_message()).await?;
+        return Ok(None);
+    };
+
+    let audit = build_audit(ctx, guild_id, command != PARTIAL_AUDIT_COMMAND).await?;
+    if audit.items.is_empty() {
+        respond_ephemeral_message(
+            &ctx.http,
+            interaction,
+            "No roles carry dangerous permissions.",
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    Ok(Some(audit))
+}
+
+/// Flagged roles ready to paginate.
+struct Audit {
+    items: Vec<String>,
+    title: &'static str,
+    /// Pagination command, recording whether the member list was complete.
+    command: &'static str,
+}
+
+/// Build one list entry per flagged role, most severe and highest roles first.
+///
+/// Role holders come from the member cache; `members_complete` says it holds
+/// exactly the guild's members.
+async fn build_audit(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    members_complete: bool,
+) -> anyhow::Result<Audit> {
+    let roles = guild_roles(ctx, guild_id).await?;
+
+    let mut flagged: Vec<(Severity, &Role, Permissions)> = roles
+        .iter()
+        .filter_map(|role| {
+            let risky = RISKY_PERMISSIONS
+                .iter()
+                .filter(|(perm, _)| role.permissions.contains(*perm))
+                .fold(Permissions::empty(), |acc, (perm, _)| acc | *perm);
+
+            let severity = RISKY_PERMISSIONS
+                .iter()
+                .filter(|(perm, _)| risky.contains(*perm))
+                .map(|(_, severity)| *severity)
+                .max()?;
+
+            Some((severity, role, risky))
+        })
+        .collect();
+
+    if flagged.is_empty() {
+        return Ok(Audit {
+            items: Vec::new(),
+            title: AUDIT_TITLE,
+            command: "audit",
+        });
+    }
+
+    flagged.sort_by(|left, right| {
+        right
+            .0
+            .cmp(&left.0)
+            .then_with(|| right.1.position.cmp(&left.1.position))
+    });
+
+    let members = ctx.cache.members(guild_id).await;
+    let members = (!members.is_empty()).then_some(members);
+
+    let items = flagged
+        .into_iter()
+        .map(|(severity, role, risky)| {
+            let holders = match &members {
+                Some(_) if role.id == guild_id.cast() => "everyone".to_owned(),
+                Some(members) => role_holders_line(
+                    members
+                        .iter()
+                        .filter(|(_, member)| member.roles.contains(&role.id))
+                        .map(|(user_id, _)| *user_id),
+                ),
+                None => "unknown (member list unavailable)".to_owned(),
+            };
+            let managed = if role.managed { " (managed)" } else { "" };
+
+            format!(
+                "{} • **{}**{}\nPermissions: {}\nMembers: {}",
+                severity.label(),
+                role.name.replace('@', "@\u{200B}"),
+                managed,
+                permission_names(risky).join(", "),
+                holders
+            )
+        })
+        .collect();
+
+    Ok(if members_complete {
+        Audit {
+            items,
+            title: AUDIT_TITLE,
+            command: "audit",
+        }
+    } else {
+        Audit {
+            items,
+            title: "Role Permission Audit (partial member list)",
+            command: PARTIAL_AUDIT_COMMAND,
+        }
+    })
+}
+
+fn role_holders_line(holders: impl Iterator<Item = Id<UserMarker>>) -> String {
+    let holders: Vec<Id<UserMarker>> = holders.collect();
+    if holders.is_empty() {
+        return "none".to_owned();
+    }
+
+    let shown = holders
+        .iter()
+        .take(MEMBERS_SHOWN_PER_ROLE)
+        .map(|user_id| format!("<@{}>", user_id.get()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match holders.len().checked_sub(MEMBERS_SHOWN_PER_ROLE) {
+        Some(hidden) if hidden > 0 => format!("{shown} (+{hidden} more)"),
+        _ => shown,
+    }
+}
+
+/// Fetch every guild member into the cache, a page at a time.
+///
+/// A full fetch replaces the guild's cached members so departed members are
+/// dropped. Returns whether the fetched list matches the guild's member count.
+/// Listing members requires the privileged members intent; without it the
+/// cache keeps only the members seen through messages and events.
+async fn refresh_member_cache(ctx: &Context, guild_id: Id<GuildMarker>) -> bool {
+    let mut fetched = Vec::new();
+    let mut after = None;
+
+    for _ in 0..MEMBER_FETCH_PAGES {
+        let mut request = ctx.http.guild_members(guild_id).limit(MEMBER_FETCH_LIMIT);
+        if let Some(user_id) = after {
+            request = request.after(user_id);
+        }
+
+        let members = match request.await {
+            Ok(response) => match response.models().await {
+                Ok(members) => members,
+                Err(source) => {
+                    debug!(?source, "failed to decode guild members");
+                    break;
+                }
+            },
+            Err(source) => {
+                debug!(?source, "failed to list guild members");
+                break;
+            }
+        };
+
+        let last_page = members.len() < usize::from(MEMBER_FETCH_LIMIT);
+        after = members.last().map(|member| member.user.id);
+        fetched.extend(members);
+
+        if last_page {
+            ctx.cache.set_members(guild_id, &fetched).await;
+            return ctx
+                .cache
+                .guild(guild_id)
+                .await
+                .is_some_and(|guild| guild.member_count == Some(fetched.len() as u64));
+        }
+    }
+
+    // Keep what was fetched, but other cached members may still be current
+    for member in &fetched {
+        ctx.cache.insert_member(guild_id, member).await;
+    }
+
+    false
+}
@@ -1,56 +1,86 @@
-use tracing::error;
+use anyhow::Context as _;
+use tracing::warn;
 use twilight_http::request::AuditLogReason as _;
 use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
 
 use crate::commands::CommandMeta;
-use crate::commands::moderation::embeds::{
-    fetch_target_profile, guild_only_message, moderation_action_embed, permission_denied_message,
-    usage_message,
-};
+use crate::commands::args::{ArgError, Args, CommandArgs};
+use crate::commands::middleware::message_guild_id;
+use crate::commands::moderation::TargetArgs;
+use crate::commands::moderation::embeds::{fetch_target_profile, moderation_action_embed};
+use crate::commands::registry::command;
 use crate::context::Context;
-use crate::util::parse::parse_target_user_id;
-use crate::util::permissions::has_message_permission;
+use crate::database::guild_config::guild_config;
+use crate::error::BotError;
 
 pub const META: CommandMeta = CommandMeta {
     name: "ban",
     desc: "Ban a user from the server.",
     category: "moderation",
-    usage: "!ban <user> [reason]",
+    usage: "!ban <user> [--delete <duration>] [--silent] [reason]",
+    aliases: &["b"],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::BAN_MEMBERS,
+    subcommands: &[],
 };
 
-/// Ban a target user after permission and input validation.
-pub async fn run(
-    ctx: Context,
-    msg: Box<MessageCreate>,
-    arg1: Option<&str>,
-    arg_tail: Option<&str>,
-) -> anyhow::Result<()> {
-    let http = &ctx.http;
-    let Some(guild_id) = msg.guild_id else {
-        http.create_message(msg.channel_id)
-            .content(guild_only_message())
-            .await?;
-        return Ok(());
-    };
+/// Longest stretch of message history Discord deletes on ban (7 days).
+const MAX_DELETE_MESSAGE_SECS: u64 = 7 * 24 * 60 * 60;
 
-    if !has_message_permission(http, &msg, Permissions::BAN_MEMBERS).await? {
-        http.create_message(msg.channel_id)
-            .content(permission_denied_message())
-            .await?;
-        return Ok(());
+/// Parsed `ban` arguments.
+struct BanArgs {
+    target: TargetArgs,
+    delete_message_secs: Option<u64>,
+    silent: bool,
+}
+
+impl BanArgs {
+    fn parse(args: &mut Args<'_>) -> Result<Self, ArgError> {
+        let user_id = args.user("user")?;
+
+        // Options sit between the user and the reason, in any order.
+        let mut delete_message_secs = None;
+        let mut silent = false;
+        loop {
+            if let Some(secs) = args.option("delete", |args| {
+                args.duration("delete", MAX_DELETE_MESSAGE_SECS)
+            })? {
+                delete_message_secs = Some(secs);
+            } else if args.switch("silent", 's') {
+                silent = true;
+            } else {
+                break;
+            }
+        }
+
+        Ok(Self {
+            target: TargetArgs {
+                user_id,
+                reason: args.optional(|args| args.rest("reason")),
+            },
+            delete_message_secs,
+            silent,
+        })
     }
+}
 
-    let Some(raw_target) = arg1 else {
-        let usage = usage_message(META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
+command!(BanCommand => run);
 
-    let Some(target_user_id) = parse_target_user_id(raw_target) else {
-        let usage = usage_message(META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
+/// Ban a target user after permission and input validation.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
+
+    let BanArgs {
+        target: TargetArgs {
+            user_id: target_user_id,
+            reason,
+        },
+        delete_message_secs,
+        silent,
+    } = BanArgs::parse(&mut args.parser(&ctx, &msg))?;
 
     if target_user_id == msg.author.id {
         http.create_message(msg.channel_id)
@@ -60,20 +90,40 @@ pub async fn run(
     }
 
     let mut request = http.create_ban(guild_id, target_user_id);
-    if let Some(reason) = arg_tail {
+    if let Some(reason) = &reason {
         request = request.reason(reason);
     }
+    if let Some(secs) = delete_message_secs {
+        request = request.delete_message_seconds(secs as u32);
+    }
 
-    if let Err(source) = request.await {
-        error!(?source, "ban request failed");
-        http.create_message(msg.channel_id)
-            .content("I couldn't ban that user. Check role hierarchy and permissions.")
-            .await?;
+    request
+        .await
+        .map_err(|source| {
+            BotError::from(source)
+                .with_permission_hint("I need the Ban Members permission and a role above theirs.")
+        })
+        .context("ban request failed")?;
+    ctx.cache.remove_member(guild_id, target_user_id).await;
+
+    // A silent ban leaves no trace in the channel beyond the audit log.
+    if silent {
+        if let Err(source) = http.delete_message(msg.channel_id, msg.id).await {
+            warn!(?source, "couldn't delete silent ban command");
+        }
         return Ok(());
     }
 
-    let target_profile = fetch_target_profile(http, target_user_id).await;
-    let embed = moderation_action_embed(&target_profile, target_user_id, "banned", arg_tail, None)?;
+    let config = guild_config(Some(guild_id.get())).await;
+    let target_profile = fetch_target_profile(&ctx, target_user_id).await;
+    let embed = moderation_action_embed(
+        &target_profile,
+        target_user_id,
+        "banned",
+        reason.as_deref(),
+        None,
+        config.embed_color,
+    )?;
     http.create_message(msg.channel_id).embeds(&[embed]).await?;
 
     Ok(())
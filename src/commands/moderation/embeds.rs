@@ -1,9 +1,10 @@
-use twilight_http::Client;
 use twilight_model::{channel::message::embed::Embed, id::Id, id::marker::UserMarker};
 use twilight_util::builder::embed::{EmbedAuthorBuilder, EmbedBuilder, ImageSource};
 
+use crate::cache::CachedUser;
+use crate::context::Context;
 use crate::database::warnings::WarningEntry;
-use crate::util::embed::DEFAULT_EMBED_COLOR;
+use crate::util::permissions::{PermissionExplanation, PermissionSource};
 
 /// Build a moderation action-result embed.
 ///
@@ -15,23 +16,20 @@ pub struct TargetProfile {
 }
 
 /// Resolve a moderation target profile for display in embeds.
-pub async fn fetch_target_profile(http: &Client, user_id: Id<UserMarker>) -> TargetProfile {
-    let user = match http.user(user_id).await {
-        Ok(response) => match response.model().await {
-            Ok(model) => model,
-            Err(_) => {
+///
+/// Uses the cached user when available and falls back to HTTP.
+pub async fn fetch_target_profile(ctx: &Context, user_id: Id<UserMarker>) -> TargetProfile {
+    let user = match ctx.cache.user(user_id).await {
+        Some(user) => user,
+        None => match fetch_user(ctx, user_id).await {
+            Some(user) => user,
+            None => {
                 return TargetProfile {
                     display_name: format!("User {}", user_id.get()),
                     avatar_url: None,
                 };
             }
         },
-        Err(_) => {
-            return TargetProfile {
-                display_name: format!("User {}", user_id.get()),
-                avatar_url: None,
-            };
-        }
     };
 
     let display_name = user.global_name.unwrap_or(user.name);
@@ -56,12 +54,19 @@ pub async fn fetch_target_profile(http: &Client, user_id: Id<UserMarker>) -> Tar
     }
 }
 
+async fn fetch_user(ctx: &Context, user_id: Id<UserMarker>) -> Option<CachedUser> {
+    let user = ctx.http.user(user_id).await.ok()?.model().await.ok()?;
+    ctx.cache.insert_user(&user).await;
+    ctx.cache.user(user_id).await
+}
+
 pub fn moderation_action_embed(
     target_profile: &TargetProfile,
     target_user_id: Id<UserMarker>,
     action_past_tense: &str,
     reason: Option<&str>,
     duration: Option<&str>,
+    color: u32,
 ) -> anyhow::Result<Embed> {
     let reason = reason
         .unwrap_or("No reason provided")
@@ -77,9 +82,7 @@ pub fn moderation_action_embed(
         None => format!("Target: <@{}>\nReason: {}", target_user_id.get(), reason),
     };
 
-    let builder = EmbedBuilder::new()
-        .color(DEFAULT_EMBED_COLOR)
-        .description(description);
+    let builder = EmbedBuilder::new().color(color).description(description);
 
     let builder = match target_profile.avatar_url.as_deref() {
         Some(url) => {
@@ -101,16 +104,24 @@ pub fn moderation_action_embed(
     Ok(builder.validate()?.build())
 }
 
-pub fn usage_message(usage: &str) -> String {
-    format!("Usage: `{usage}`")
-}
-
 pub fn guild_only_message() -> &'static str {
     "This command only works in servers."
 }
 
-pub fn permission_denied_message() -> &'static str {
-    "You are not permitted to use this command."
+/// Render one permission with the overwrite or rule that decided it.
+pub fn permission_explanation_line(explanation: &PermissionExplanation) -> String {
+    let marker = if explanation.granted { "✅" } else { "❌" };
+    let source = match explanation.source {
+        PermissionSource::Owner => "server owner".to_owned(),
+        PermissionSource::Administrator => "administrator role".to_owned(),
+        PermissionSource::Roles => "roles".to_owned(),
+        PermissionSource::EveryoneOverwrite => "@everyone overwrite".to_owned(),
+        PermissionSource::RoleOverwrite(role_id) => format!("<@&{}> overwrite", role_id.get()),
+        PermissionSource::MemberOverwrite => "member overwrite".to_owned(),
+        PermissionSource::Timeout => "timed out".to_owned(),
+    };
+
+    format!("{marker} {} ({source})", explanation.name)
 }
 
 pub fn warnings_window_label_days(days: u64) -> String {
@@ -121,6 +132,7 @@ pub fn warnings_overview_embed(
     target_profile: &TargetProfile,
     window_label: &str,
     entries: &[WarningEntry],
+    color: u32,
 ) -> anyhow::Result<Embed> {
     let count = entries.len();
     let mut description = format!("Total warnings in {}: **{}**\n\n", window_label, count);
@@ -142,9 +154,7 @@ pub fn warnings_overview_embed(
     }
 
     let title = format!("Warnings for {}", target_profile.display_name);
-    let builder = EmbedBuilder::new()
-        .color(DEFAULT_EMBED_COLOR)
-        .description(description);
+    let builder = EmbedBuilder::new().color(color).description(description);
 
     let builder = match target_profile.avatar_url.as_deref() {
         Some(url) => {
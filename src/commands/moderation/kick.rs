@@ -1,53 +1,41 @@
-use tracing::error;
+use anyhow::Context as _;
 use twilight_http::request::AuditLogReason as _;
 use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
 
 use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
+use crate::commands::moderation::TargetArgs;
 use crate::commands::moderation::embeds::{fetch_target_profile, moderation_action_embed};
+use crate::commands::registry::command;
 use crate::context::Context;
-use crate::util::parse::parse_target_user_id;
-use crate::util::permissions::has_message_permission;
+use crate::database::guild_config::guild_config;
+use crate::error::BotError;
 
 pub const META: CommandMeta = CommandMeta {
     name: "kick",
     desc: "Kick a user from the server.",
     category: "moderation",
     usage: "!kick <user> [reason]",
+    aliases: &["k"],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::KICK_MEMBERS,
+    subcommands: &[],
 };
 
+command!(KickCommand => run);
+
 /// Kick a target user after permission and input validation.
-pub async fn run(
-    ctx: Context,
-    msg: Box<MessageCreate>,
-    arg1: Option<&str>,
-    arg_tail: Option<&str>,
-) -> anyhow::Result<()> {
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
     let http = &ctx.http;
-    let Some(guild_id) = msg.guild_id else {
-        http.create_message(msg.channel_id)
-            .content("This command only works in servers.")
-            .await?;
-        return Ok(());
-    };
-
-    if !has_message_permission(http, &msg, Permissions::KICK_MEMBERS).await? {
-        http.create_message(msg.channel_id)
-            .content("You are not permitted to use this command.")
-            .await?;
-        return Ok(());
-    }
-
-    let Some(raw_target) = arg1 else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
+    let guild_id = message_guild_id(&msg)?;
 
-    let Some(target_user_id) = parse_target_user_id(raw_target) else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
+    let TargetArgs {
+        user_id: target_user_id,
+        reason,
+    } = TargetArgs::parse(&mut args.parser(&ctx, &msg))?;
 
     if target_user_id == msg.author.id {
         http.create_message(msg.channel_id)
@@ -57,20 +45,29 @@ pub async fn run(
     }
 
     let mut request = http.remove_guild_member(guild_id, target_user_id);
-    if let Some(reason) = arg_tail {
+    if let Some(reason) = &reason {
         request = request.reason(reason);
     }
 
-    if let Err(source) = request.await {
-        error!(?source, "kick request failed");
-        http.create_message(msg.channel_id)
-            .content("I couldn't kick that user. Check role hierarchy and permissions.")
-            .await?;
-        return Ok(());
-    }
+    request
+        .await
+        .map_err(|source| {
+            BotError::from(source)
+                .with_permission_hint("I need the Kick Members permission and a role above theirs.")
+        })
+        .context("kick request failed")?;
+    ctx.cache.remove_member(guild_id, target_user_id).await;
 
-    let target_profile = fetch_target_profile(http, target_user_id).await;
-    let embed = moderation_action_embed(&target_profile, target_user_id, "kicked", arg_tail, None)?;
+    let config = guild_config(Some(guild_id.get())).await;
+    let target_profile = fetch_target_profile(&ctx, target_user_id).await;
+    let embed = moderation_action_embed(
+        &target_profile,
+        target_user_id,
+        "kicked",
+        reason.as_deref(),
+        None,
+        config.embed_color,
+    )?;
     http.create_message(msg.channel_id).embeds(&[embed]).await?;
 
     Ok(())
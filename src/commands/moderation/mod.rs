@@ -1,10 +1,31 @@
+pub mod audit;
 pub mod ban;
 mod embeds;
 pub mod kick;
 pub mod permissions;
 pub mod purge;
+pub mod roles;
 pub mod timeout;
 pub mod unban;
 pub mod untimeout;
 pub mod warn;
 pub mod warnings;
+
+use twilight_model::id::{Id, marker::UserMarker};
+
+use crate::commands::args::{ArgError, Args};
+
+/// `<user> [reason]`, shared by the single-target moderation commands.
+pub struct TargetArgs {
+    pub user_id: Id<UserMarker>,
+    pub reason: Option<String>,
+}
+
+impl TargetArgs {
+    pub fn parse(args: &mut Args<'_>) -> Result<Self, ArgError> {
+        Ok(Self {
+            user_id: args.user("user")?,
+            reason: args.optional(|args| args.rest("reason")),
+        })
+    }
+}
@@ -1,62 +1,89 @@
 use std::sync::Arc;
-use twilight_model::gateway::payload::incoming::{InteractionCreate, MessageCreate};
+use twilight_http::Error as HttpError;
+use twilight_model::{
+    gateway::payload::incoming::{InteractionCreate, MessageCreate},
+    guild::Permissions,
+    id::{
+        Id,
+        marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+    },
+};
 
-use crate::commands::CommandMeta;
+use crate::commands::args::{Args, CommandArgs};
+use crate::commands::middleware::message_guild_id;
+use crate::commands::moderation::embeds::{fetch_target_profile, permission_explanation_line};
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown};
 use crate::context::Context;
+use crate::database::guild_config::guild_config;
+use crate::error::BotError;
 use crate::util::pagination::{
-    DEFAULT_TIMEOUT_SECS, PaginationInteractionValidation, PaginationModalSubmitValidation,
-    build_paginated_list_view, clamp_page, open_jump_modal_from_token, parse_one_based_page,
-    resolve_modal_target_page, respond_ephemeral_message, send_paginated_message, total_pages,
-    update_paginated_interaction_message, validate_interaction_for_command,
-    validate_jump_modal_for_command,
+    PaginationInteractionValidation, PaginationModalSubmitValidation, build_paginated_list_view,
+    clamp_page, open_jump_modal_from_token, resolve_modal_target_page, respond_ephemeral_message,
+    send_paginated_message, timeout_secs, total_pages, update_paginated_interaction_message,
+    validate_interaction_for_command_prefix, validate_jump_modal_for_command_prefix,
+};
+use crate::util::parse::{parse_channel_id, parse_role_id, parse_target_user_id};
+use crate::util::permissions::{
+    explain_member_permissions, fetch_channel, guild_member, guild_roles, permission_names,
+    resolve_member_permissions,
 };
-use crate::util::permissions::{permission_names, resolve_message_author_permissions};
 
 pub const META: CommandMeta = CommandMeta {
     name: "permissions",
-    desc: "Display your server permissions.",
+    desc: "Display server permissions for you, a member, or a role.",
     category: "moderation",
-    usage: "!permissions [page]",
+    usage: "!permissions [user] [channel] [page] | !permissions role <role> [page]",
+    aliases: &["perms"],
+    cooldown: Some(Cooldown::per_user(5)),
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
 };
 
+command!(PermissionsCommand => run, paginated);
+
 const PERMISSIONS_PER_PAGE: usize = 10;
 
-/// Display invoking member permissions in a paginated embed.
-pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> anyhow::Result<()> {
-    let http = &ctx.http;
-    let perms = match resolve_message_author_permissions(http, &msg).await? {
-        Some(perms) => perms,
-        None => {
-            http.create_message(msg.channel_id)
-                .content("This command only works in servers.")
-                .await?;
-            return Ok(());
-        }
-    };
+/// Page arguments are short numbers; anything longer is treated as a snowflake ID.
+const MAX_PAGE_DIGITS: usize = 6;
 
-    if perms.is_empty() {
-        http.create_message(msg.channel_id)
-            .content("No permissions found for your member record.")
-            .await?;
-        return Ok(());
-    }
+/// Whose permissions a `permissions` view displays.
+#[derive(Clone, Copy)]
+enum PermissionsTarget {
+    Member {
+        user_id: Id<UserMarker>,
+        channel_id: Option<Id<ChannelMarker>>,
+    },
+    Role(Id<RoleMarker>),
+}
 
-    let names = permission_names(perms);
+/// Rendered permissions listing, or a user-facing reason it can't be shown.
+enum PermissionsView {
+    Ready { title: String, items: Vec<String> },
+    Unavailable(&'static str),
+}
 
-    if names.is_empty() {
-        http.create_message(msg.channel_id)
-            .content("You have no permissions set.")
-            .await?;
-        return Ok(());
-    }
+/// Display permissions for the author, another member (optionally in a channel), or a role.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
 
-    let total_pages = total_pages(names.len(), PERMISSIONS_PER_PAGE);
-    let Some(requested_page) = parse_one_based_page(arg1) else {
-        let usage = format!("Usage: `{}` (page starts at 1)", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
+    let mut args = args.parser(&ctx, &msg);
+    let target = parse_target(&mut args, msg.author.id).await?;
+    let requested_page = args.page()?;
+
+    let (title, items) = match build_view(&ctx, guild_id, msg.author.id, target).await? {
+        PermissionsView::Ready { title, items } => (title, items),
+        PermissionsView::Unavailable(reason) => {
+            http.create_message(msg.channel_id).content(reason).await?;
+            return Ok(());
+        }
     };
 
+    let total_pages = total_pages(items.len(), PERMISSIONS_PER_PAGE);
+
     if requested_page > total_pages {
         let msg_out = format!(
             "Page {} does not exist. Available pages: 1-{}.",
@@ -68,14 +95,16 @@ pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> a
         return Ok(());
     }
 
+    let embed_color = guild_config(Some(guild_id.get())).await.embed_color;
     let (embed, components) = build_paginated_list_view(
-        "permissions",
-        "Your Server Permissions",
-        &names,
+        &pagination_command(target, msg.author.id),
+        &title,
+        embed_color,
+        &items,
         requested_page,
         PERMISSIONS_PER_PAGE,
         msg.author.id.get(),
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )?;
 
     send_paginated_message(
@@ -84,7 +113,7 @@ pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> a
         embed,
         components,
         total_pages,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
@@ -98,7 +127,7 @@ pub async fn handle_pagination_interaction(
 ) -> anyhow::Result<bool> {
     let http = &ctx.http;
     let (actor_id, token) =
-        match validate_interaction_for_command(http, &interaction, "permissions").await? {
+        match validate_interaction_for_command_prefix(http, &interaction, "permissions").await? {
             PaginationInteractionValidation::NotForCommand => return Ok(false),
             PaginationInteractionValidation::HandledInvalid => return Ok(true),
             PaginationInteractionValidation::Valid {
@@ -107,28 +136,13 @@ pub async fn handle_pagination_interaction(
             } => (actor_user_id, token),
         };
 
-    let Some(perms) = interaction
-        .member
-        .as_ref()
-        .and_then(|member| member.permissions)
+    let Some((title, items)) =
+        interaction_view(&ctx, &interaction, actor_id, &token.command).await?
     else {
-        respond_ephemeral_message(
-            http,
-            &interaction,
-            "Unable to resolve member permissions for this interaction.",
-        )
-        .await?;
         return Ok(true);
     };
 
-    let names = permission_names(perms);
-    if names.is_empty() {
-        respond_ephemeral_message(http, &interaction, "No permissions available for display.")
-            .await?;
-        return Ok(true);
-    }
-
-    let total_pages = total_pages(names.len(), PERMISSIONS_PER_PAGE);
+    let total_pages = total_pages(items.len(), PERMISSIONS_PER_PAGE);
 
     if token.action == "jump" {
         open_jump_modal_from_token(http, &interaction, &token, total_pages).await?;
@@ -136,14 +150,18 @@ pub async fn handle_pagination_interaction(
     }
 
     let target_page = clamp_page(token.page, total_pages);
+    let embed_color = guild_config(interaction.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
     let (embed, components) = build_paginated_list_view(
-        "permissions",
-        "Your Server Permissions",
-        &names,
+        &token.command,
+        &title,
+        embed_color,
+        &items,
         target_page,
         PERMISSIONS_PER_PAGE,
         actor_id,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )?;
 
     update_paginated_interaction_message(
@@ -152,7 +170,7 @@ pub async fn handle_pagination_interaction(
         embed,
         components,
         total_pages,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
@@ -165,50 +183,38 @@ pub async fn handle_pagination_modal_interaction(
     interaction: Box<InteractionCreate>,
 ) -> anyhow::Result<bool> {
     let http = &ctx.http;
-    let (actor_id, entered_page, total_pages_hint) =
-        match validate_jump_modal_for_command(http, &interaction, "permissions").await? {
+    let (actor_id, command, entered_page, total_pages_hint) =
+        match validate_jump_modal_for_command_prefix(http, &interaction, "permissions").await? {
             PaginationModalSubmitValidation::NotForCommand => return Ok(false),
             PaginationModalSubmitValidation::HandledInvalid => return Ok(true),
             PaginationModalSubmitValidation::Valid {
                 actor_user_id,
+                command,
                 requested_page,
                 total_pages_hint,
-                ..
-            } => (actor_user_id, requested_page, total_pages_hint),
+            } => (actor_user_id, command, requested_page, total_pages_hint),
         };
 
-    let Some(perms) = interaction
-        .member
-        .as_ref()
-        .and_then(|member| member.permissions)
+    let Some((title, items)) = interaction_view(&ctx, &interaction, actor_id, &command).await?
     else {
-        respond_ephemeral_message(
-            http,
-            &interaction,
-            "Unable to resolve member permissions for this interaction.",
-        )
-        .await?;
         return Ok(true);
     };
 
-    let names = permission_names(perms);
-    if names.is_empty() {
-        respond_ephemeral_message(http, &interaction, "No permissions available for display.")
-            .await?;
-        return Ok(true);
-    }
-
-    let total_pages: usize = total_pages(names.len(), PERMISSIONS_PER_PAGE);
+    let total_pages: usize = total_pages(items.len(), PERMISSIONS_PER_PAGE);
     let target_page = resolve_modal_target_page(entered_page, total_pages, total_pages_hint);
 
+    let embed_color = guild_config(interaction.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
     let (embed, components) = build_paginated_list_view(
-        "permissions",
-        "Your Server Permissions",
-        &names,
+        &command,
+        &title,
+        embed_color,
+        &items,
         target_page,
         PERMISSIONS_PER_PAGE,
         actor_id,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )?;
 
     update_paginated_interaction_message(
@@ -217,9 +223,244 @@ pub async fn handle_pagination_modal_interaction(
         embed,
         components,
         total_pages,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
     Ok(true)
 }
+
+/// Rebuild the listing for a pagination interaction, responding when it can't be shown.
+async fn interaction_view(
+    ctx: &Context,
+    interaction: &InteractionCreate,
+    actor_id: u64,
+    command: &str,
+) -> anyhow::Result<Option<(String, Vec<String>)>> {
+    let http = &ctx.http;
+    let actor_id = Id::new(actor_id);
+
+    let (Some(guild_id), Some(target)) = (
+        interaction.guild_id,
+        target_from_pagination_command(command, actor_id),
+    ) else {
+        respond_ephemeral_message(
+            http,
+            interaction,
+            "Unable to resolve member permissions for this interaction.",
+        )
+        .await?;
+        return Ok(None);
+    };
+
+    match build_view(ctx, guild_id, actor_id, target).await? {
+        PermissionsView::Ready { title, items } => Ok(Some((title, items))),
+        PermissionsView::Unavailable(reason) => {
+            respond_ephemeral_message(http, interaction, reason).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Parse `[user] [channel]` or `role <role>` into a target, leaving the page.
+async fn parse_target(
+    args: &mut Args<'_>,
+    author_id: Id<UserMarker>,
+) -> anyhow::Result<PermissionsTarget> {
+    if args
+        .optional(|args| args.choice("target", &["role"]))
+        .is_some()
+    {
+        let role = args.role("role").await?;
+        return Ok(PermissionsTarget::Role(role.id));
+    }
+
+    let user_id = if args
+        .peek()
+        .is_some_and(|word| !is_page_token(word) && !word.starts_with("<#"))
+    {
+        args.user("user")?
+    } else {
+        author_id
+    };
+
+    let channel_id = if args.peek().is_some_and(|word| !is_page_token(word)) {
+        Some(args.channel("channel").await?.id)
+    } else {
+        None
+    };
+
+    Ok(PermissionsTarget::Member {
+        user_id,
+        channel_id,
+    })
+}
+
+fn is_page_token(raw: &str) -> bool {
+    raw.len() <= MAX_PAGE_DIGITS && raw.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Build the title and list items for a permissions target.
+async fn build_view(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    author_id: Id<UserMarker>,
+    target: PermissionsTarget,
+) -> anyhow::Result<PermissionsView> {
+    match target {
+        PermissionsTarget::Role(role_id) => {
+            let roles = guild_roles(ctx, guild_id).await?;
+            let Some(role) = roles.iter().find(|role| role.id == role_id) else {
+                return Ok(PermissionsView::Unavailable("I couldn't find that role."));
+            };
+
+            let items = permission_names(role.permissions);
+            if items.is_empty() {
+                return Ok(PermissionsView::Unavailable(
+                    "That role has no permissions.",
+                ));
+            }
+
+            Ok(PermissionsView::Ready {
+                title: format!("Permissions for role {}", role.name),
+                items,
+            })
+        }
+        PermissionsTarget::Member {
+            user_id,
+            channel_id,
+        } => {
+            let member = match guild_member(ctx, guild_id, user_id).await {
+                Ok(member) => member,
+                Err(source) => match source.downcast::<HttpError>().map(BotError::from) {
+                    Ok(BotError::UnknownMember(_) | BotError::UnknownUser(_)) => {
+                        return Ok(PermissionsView::Unavailable(
+                            "That user is not a member of this server.",
+                        ));
+                    }
+                    Ok(bot_error) => return Err(bot_error.into()),
+                    Err(source) => return Err(source),
+                },
+            };
+
+            let items = match channel_id {
+                Some(channel_id) => {
+                    let channel = match fetch_channel(ctx, channel_id).await {
+                        Ok(channel) => channel,
+                        Err(source) => match source.downcast::<HttpError>().map(BotError::from) {
+                            Ok(BotError::UnknownChannel(_)) => {
+                                return Ok(PermissionsView::Unavailable(
+                                    "I couldn't find that channel.",
+                                ));
+                            }
+                            Ok(bot_error) => return Err(bot_error.into()),
+                            Err(source) => return Err(source),
+                        },
+                    };
+                    if channel.guild_id != Some(guild_id) {
+                        return Ok(PermissionsView::Unavailable(
+                            "That channel is not in this server.",
+                        ));
+                    }
+
+                    explain_member_permissions(ctx, guild_id, user_id, &member, Some(channel_id))
+                        .await?
+                        .iter()
+                        .map(permission_explanation_line)
+                        .collect()
+                }
+                None => permission_names(
+                    resolve_member_permissions(ctx, guild_id, user_id, &member, None).await?,
+                ),
+            };
+
+            if items.is_empty() {
+                let reason = if user_id == author_id {
+                    "No permissions found for your member record."
+                } else {
+                    "No permissions found for that member."
+                };
+                return Ok(PermissionsView::Unavailable(reason));
+            }
+
+            let title = match (user_id == author_id, channel_id) {
+                (true, None) => "Your Server Permissions".to_owned(),
+                (true, Some(channel_id)) => {
+                    format!(
+                        "Your Permissions in #{}",
+                        channel_name(ctx, channel_id).await
+                    )
+                }
+                (false, None) => {
+                    let profile = fetch_target_profile(ctx, user_id).await;
+                    format!("Permissions for {}", profile.display_name)
+                }
+                (false, Some(channel_id)) => {
+                    let profile = fetch_target_profile(ctx, user_id).await;
+                    format!(
+                        "Permissions for {} in #{}",
+                        profile.display_name,
+                        channel_name(ctx, channel_id).await
+                    )
+                }
+            };
+
+            Ok(PermissionsView::Ready { title, items })
+        }
+    }
+}
+
+async fn channel_name(ctx: &Context, channel_id: Id<ChannelMarker>) -> String {
+    fetch_channel(ctx, channel_id)
+        .await
+        .ok()
+        .and_then(|channel| channel.name)
+        .unwrap_or_else(|| channel_id.get().to_string())
+}
+
+/// Encode a target into the pagination command key (`permissions|u:<id>|c:<id>`).
+fn pagination_command(target: PermissionsTarget, author_id: Id<UserMarker>) -> String {
+    match target {
+        PermissionsTarget::Member {
+            user_id,
+            channel_id: None,
+        } if user_id == author_id => "permissions".to_owned(),
+        PermissionsTarget::Member {
+            user_id,
+            channel_id: None,
+        } => format!("permissions|u:{user_id}"),
+        PermissionsTarget::Member {
+            user_id,
+            channel_id: Some(channel_id),
+        } => format!("permissions|u:{user_id}|c:{channel_id}"),
+        PermissionsTarget::Role(role_id) => format!("permissions|r:{role_id}"),
+    }
+}
+
+fn target_from_pagination_command(
+    command: &str,
+    actor_id: Id<UserMarker>,
+) -> Option<PermissionsTarget> {
+    let Some(rest) = command.strip_prefix("permissions|") else {
+        return (command == "permissions").then_some(PermissionsTarget::Member {
+            user_id: actor_id,
+            channel_id: None,
+        });
+    };
+
+    if let Some(raw_role) = rest.strip_prefix("r:") {
+        return parse_role_id(raw_role).map(PermissionsTarget::Role);
+    }
+
+    let mut parts = rest.split('|');
+    let user_id = parse_target_user_id(parts.next()?.strip_prefix("u:")?)?;
+    let channel_id = match parts.next() {
+        Some(raw_channel) => Some(parse_channel_id(raw_channel.strip_prefix("c:")?)?),
+        None => None,
+    };
+
+    Some(PermissionsTarget::Member {
+        user_id,
+        channel_id,
+    })
+}
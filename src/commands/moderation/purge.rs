@@ -1,56 +1,43 @@
+use anyhow::Context as _;
 use tokio::time::{Duration, sleep};
-use tracing::error;
 use twilight_model::{
     gateway::payload::incoming::MessageCreate,
     guild::Permissions,
     id::{Id, marker::MessageMarker},
 };
 
-use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown};
 use crate::context::Context;
-use crate::util::permissions::has_message_permission;
+use crate::database::guild_config::{BULK_DELETE_LIMIT, guild_config};
+use crate::error::BotError;
 
 pub const META: CommandMeta = CommandMeta {
     name: "purge",
     desc: "Delete the latest messages in this channel.",
     category: "moderation",
     usage: "!purge <amount>",
+    aliases: &["clear"],
+    cooldown: Some(Cooldown::per_channel(5)),
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MANAGE_MESSAGES,
+    subcommands: &[],
 };
 
-const MAX_PURGE: u16 = 100;
+command!(PurgeCommand => run);
 
 /// Delete a bounded number of recent channel messages.
-pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> anyhow::Result<()> {
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
     let http = &ctx.http;
-    let Some(requested_raw) = arg1 else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
-
-    let Ok(requested) = requested_raw.parse::<u16>() else {
-        http.create_message(msg.channel_id)
-            .content("Amount must be a number between 1 and 100.")
-            .await?;
-        return Ok(());
-    };
-
-    if requested == 0 {
-        http.create_message(msg.channel_id)
-            .content("Amount must be at least 1.")
-            .await?;
-        return Ok(());
-    };
-
-    let amount = requested.min(MAX_PURGE);
-    let delete_count = amount.saturating_add(1).min(MAX_PURGE);
-
-    if !has_message_permission(http, &msg, Permissions::MANAGE_MESSAGES).await? {
-        http.create_message(msg.channel_id)
-            .content("You are not permitted to use this command.")
-            .await?;
-        return Ok(());
-    }
+    let config = guild_config(msg.guild_id.map(|id| id.get())).await;
+    // Larger requests are clamped to the guild's limit rather than refused.
+    let amount = args
+        .parser(&ctx, &msg)
+        .integer("amount", 1..=u16::MAX)?
+        .min(config.purge_max);
+    let delete_count = amount.saturating_add(1).min(BULK_DELETE_LIMIT);
 
     let messages = http
         .channel_messages(msg.channel_id)
@@ -74,13 +61,12 @@ pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> a
         http.delete_messages(msg.channel_id, &ids).await
     };
 
-    if let Err(source) = delete_result {
-        error!(?source, "purge delete request failed");
-        http.create_message(msg.channel_id)
-            .content("I couldn't delete messages. I likely need the 'Manage Messages' permission.")
-            .await?;
-        return Ok(());
-    }
+    delete_result
+        .map_err(|source| {
+            BotError::from(source)
+                .with_permission_hint("I need the Manage Messages permission in this channel.")
+        })
+        .context("purge delete request failed")?;
 
     let confirmation = format!("Purged {} message(s).", amount);
     let confirmation_message = http
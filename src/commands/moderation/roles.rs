@@ -0,0 +1,113 @@
+use std::cmp::Ordering;
+
+use twilight_model::{
+    channel::message::embed::Embed,
+    gateway::payload::incoming::MessageCreate,
+    guild::{Permissions, Role},
+};
+use twilight_util::builder::embed::{EmbedBuilder, EmbedFieldBuilder};
+
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown};
+use crate::context::Context;
+use crate::database::guild_config::guild_config;
+use crate::util::permissions::permission_names;
+
+pub const META: CommandMeta = CommandMeta {
+    name: "roles",
+    desc: "Compare the permissions of two roles.",
+    category: "moderation",
+    usage: "!roles diff <role A> <role B>",
+    aliases: &[],
+    cooldown: Some(Cooldown::per_user(5)),
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MANAGE_ROLES,
+    subcommands: &[],
+};
+
+command!(RolesCommand => run);
+
+/// Discord rejects embed field values longer than this.
+const FIELD_VALUE_LIMIT: usize = 1024;
+
+/// Compare two roles' permissions and hierarchy positions.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
+
+    let mut args = args.parser(&ctx, &msg);
+    args.choice("action", &["diff"])?;
+    let left = args.role("role A").await?;
+    let right = args.role("role B").await?;
+
+    let config = guild_config(Some(guild_id.get())).await;
+    let embed = role_diff_embed(&left, &right, config.embed_color)?;
+    http.create_message(msg.channel_id).embeds(&[embed]).await?;
+
+    Ok(())
+}
+
+fn role_diff_embed(left: &Role, right: &Role, color: u32) -> anyhow::Result<Embed> {
+    let only_left = left.permissions - right.permissions;
+    let only_right = right.permissions - left.permissions;
+    let shared = left.permissions & right.permissions;
+
+    let mut description = format!(
+        "**A:** <@&{}> (position {})\n**B:** <@&{}> (position {})\n{}",
+        left.id.get(),
+        left.position,
+        right.id.get(),
+        right.position,
+        position_summary(left, right)
+    );
+
+    if left.permissions.contains(Permissions::ADMINISTRATOR)
+        || right.permissions.contains(Permissions::ADMINISTRATOR)
+    {
+        description.push_str(
+            "\n\n⚠️ ADMINISTRATOR grants every permission regardless of the lists below.",
+        );
+    }
+
+    let embed = EmbedBuilder::new()
+        .color(color)
+        .title("Role Permission Diff")
+        .description(description)
+        .field(EmbedFieldBuilder::new("Only in A", field_value(only_left)))
+        .field(EmbedFieldBuilder::new("Only in B", field_value(only_right)))
+        .field(EmbedFieldBuilder::new("Shared", field_value(shared)))
+        .validate()?
+        .build();
+
+    Ok(embed)
+}
+
+fn position_summary(left: &Role, right: &Role) -> String {
+    let gap = left.position.abs_diff(right.position);
+    match left.position.cmp(&right.position) {
+        Ordering::Greater => format!("A is {gap} position(s) above B."),
+        Ordering::Less => format!("A is {gap} position(s) below B."),
+        Ordering::Equal => "Both roles share the same position.".to_owned(),
+    }
+}
+
+fn field_value(perms: Permissions) -> String {
+    let names = permission_names(perms);
+    if names.is_empty() {
+        return "none".to_owned();
+    }
+
+    let value = names.join(", ");
+    if value.len() <= FIELD_VALUE_LIMIT {
+        return value;
+    }
+
+    let mut cut = FIELD_VALUE_LIMIT - "…".len();
+    while !value.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!("{}…", &value[..cut])
+}
@@ -1,59 +1,68 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use tracing::error;
+use anyhow::Context as _;
 use twilight_http::request::AuditLogReason as _;
 use twilight_model::{
-    gateway::payload::incoming::MessageCreate, guild::Permissions, util::Timestamp,
+    gateway::payload::incoming::MessageCreate,
+    guild::Permissions,
+    id::{Id, marker::UserMarker},
+    util::Timestamp,
 };
 
 use crate::commands::CommandMeta;
+use crate::commands::args::{Args, CommandArgs};
+use crate::commands::middleware::message_guild_id;
 use crate::commands::moderation::embeds::{fetch_target_profile, moderation_action_embed};
+use crate::commands::registry::command;
 use crate::context::Context;
-use crate::util::parse::{parse_duration_seconds, parse_target_user_id};
-use crate::util::permissions::has_message_permission;
+use crate::database::guild_config::{MAX_TIMEOUT_SECS, guild_config};
+use crate::error::BotError;
+use crate::util::parse::format_duration_seconds;
 
 pub const META: CommandMeta = CommandMeta {
     name: "timeout",
-    desc: "Timeout a user for a duration (default: 10m).",
+    desc: "Timeout a user for a duration (default: 10m, configurable).",
     category: "moderation",
-    usage: "!timeout <user> [duration] [reason]",
+    usage: "!timeout <member> [duration] [reason]",
+    aliases: &["to", "mute"],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MODERATE_MEMBERS,
+    subcommands: &[],
 };
 
-const DEFAULT_TIMEOUT_SECS: u64 = 10 * 60;
+/// Parsed `timeout` arguments.
+struct TimeoutArgs {
+    user_id: Id<UserMarker>,
+    duration_secs: Option<u64>,
+    reason: Option<String>,
+}
 
-/// Apply a temporary communication timeout to a target user.
-pub async fn run(
-    ctx: Context,
-    msg: Box<MessageCreate>,
-    arg1: Option<&str>,
-    arg_tail: Option<&str>,
-) -> anyhow::Result<()> {
-    let http = &ctx.http;
-    let Some(guild_id) = msg.guild_id else {
-        http.create_message(msg.channel_id)
-            .content("This command only works in servers.")
-            .await?;
-        return Ok(());
-    };
+impl TimeoutArgs {
+    async fn parse(args: &mut Args<'_>) -> anyhow::Result<Self> {
+        let (user_id, _member) = args.member("member").await?;
 
-    if !has_message_permission(http, &msg, Permissions::MODERATE_MEMBERS).await? {
-        http.create_message(msg.channel_id)
-            .content("You are not permitted to use this command.")
-            .await?;
-        return Ok(());
+        Ok(Self {
+            user_id,
+            duration_secs: args.optional_duration("duration", MAX_TIMEOUT_SECS)?,
+            reason: args.optional(|args| args.rest("reason")),
+        })
     }
+}
 
-    let Some(raw_target) = arg1 else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
+command!(TimeoutCommand => run);
 
-    let Some(target_user_id) = parse_target_user_id(raw_target) else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
+/// Apply a temporary communication timeout to a target user.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = message_guild_id(&msg)?;
+
+    let TimeoutArgs {
+        user_id: target_user_id,
+        duration_secs,
+        reason,
+    } = TimeoutArgs::parse(&mut args.parser(&ctx, &msg)).await?;
 
     if target_user_id == msg.author.id {
         http.create_message(msg.channel_id)
@@ -62,22 +71,9 @@ pub async fn run(
         return Ok(());
     }
 
-    let (duration_secs, duration_label, reason) = match arg_tail {
-        Some(tail) => {
-            let mut parts = tail.splitn(2, char::is_whitespace);
-            let first = parts.next().unwrap_or("");
-            if let Some(parsed_duration) = parse_duration_seconds(first) {
-                let parsed_reason = parts
-                    .next()
-                    .map(str::trim)
-                    .filter(|value| !value.is_empty());
-                (parsed_duration, first.to_owned(), parsed_reason)
-            } else {
-                (DEFAULT_TIMEOUT_SECS, "10m".to_owned(), Some(tail))
-            }
-        }
-        None => (DEFAULT_TIMEOUT_SECS, "10m".to_owned(), None),
-    };
+    let config = guild_config(Some(guild_id.get())).await;
+    let duration_secs = duration_secs.unwrap_or(config.timeout_secs);
+    let duration_label = format_duration_seconds(duration_secs);
 
     let expires_at_secs = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -95,25 +91,30 @@ pub async fn run(
         .update_guild_member(guild_id, target_user_id)
         .communication_disabled_until(Some(expires_at));
 
-    if let Some(reason) = reason {
+    if let Some(reason) = &reason {
         request = request.reason(reason);
     }
 
-    if let Err(source) = request.await {
-        error!(?source, "timeout request failed");
-        http.create_message(msg.channel_id)
-            .content("I couldn't timeout that user. Check role hierarchy and permissions.")
-            .await?;
-        return Ok(());
-    }
-
-    let target_profile = fetch_target_profile(http, target_user_id).await;
+    let member = request
+        .await
+        .map_err(|source| {
+            BotError::from(source).with_permission_hint(
+                "I need the Timeout Members permission and a role above theirs.",
+            )
+        })
+        .context("timeout request failed")?
+        .model()
+        .await?;
+    ctx.cache.insert_member(guild_id, &member).await;
+
+    let target_profile = fetch_target_profile(&ctx, target_user_id).await;
     let embed = moderation_action_embed(
         &target_profile,
         target_user_id,
         "timed out",
-        reason,
+        reason.as_deref(),
         Some(&duration_label),
+        config.embed_color,
     )?;
     http.create_message(msg.channel_id).embeds(&[embed]).await?;
 
@@ -1,11 +1,15 @@
-use tracing::error;
+use anyhow::Context as _;
 use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
 
 use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
+use crate::commands::moderation::TargetArgs;
 use crate::commands::moderation::embeds::{fetch_target_profile, moderation_action_embed};
+use crate::commands::registry::command;
 use crate::context::Context;
-use crate::util::parse::parse_target_user_id;
-use crate::util::permissions::has_message_permission;
+use crate::database::guild_config::guild_config;
+use crate::error::BotError;
 
 use twilight_http::request::AuditLogReason as _;
 
@@ -14,58 +18,48 @@ pub const META: CommandMeta = CommandMeta {
     desc: "Unban a user from the server.",
     category: "moderation",
     usage: "!unban <user> [reason]",
+    aliases: &[],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::BAN_MEMBERS,
+    subcommands: &[],
 };
 
+command!(UnbanCommand => run);
+
 /// Remove an active ban for a target user.
-pub async fn run(
-    ctx: Context,
-    msg: Box<MessageCreate>,
-    arg1: Option<&str>,
-    arg_tail: Option<&str>,
-) -> anyhow::Result<()> {
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
     let http = &ctx.http;
-    let Some(guild_id) = msg.guild_id else {
-        http.create_message(msg.channel_id)
-            .content("This command only works in servers.")
-            .await?;
-        return Ok(());
-    };
-
-    if !has_message_permission(http, &msg, Permissions::BAN_MEMBERS).await? {
-        http.create_message(msg.channel_id)
-            .content("You are not permitted to use this command.")
-            .await?;
-        return Ok(());
-    }
+    let guild_id = message_guild_id(&msg)?;
 
-    let Some(raw_target) = arg1 else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
-
-    let Some(target_user_id) = parse_target_user_id(raw_target) else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
+    let TargetArgs {
+        user_id: target_user_id,
+        reason,
+    } = TargetArgs::parse(&mut args.parser(&ctx, &msg))?;
 
     let mut request = http.delete_ban(guild_id, target_user_id);
-    if let Some(reason) = arg_tail {
+    if let Some(reason) = &reason {
         request = request.reason(reason);
     }
 
-    if let Err(source) = request.await {
-        error!(?source, "unban request failed");
-        http.create_message(msg.channel_id)
-            .content("I couldn't unban that user. They may not be banned, or I lack permissions.")
-            .await?;
-        return Ok(());
-    }
+    request
+        .await
+        .map_err(|source| {
+            BotError::from(source).with_permission_hint("I need the Ban Members permission.")
+        })
+        .context("unban request failed")?;
 
-    let target_profile = fetch_target_profile(http, target_user_id).await;
-    let embed =
-        moderation_action_embed(&target_profile, target_user_id, "unbanned", arg_tail, None)?;
+    let config = guild_config(Some(guild_id.get())).await;
+    let target_profile = fetch_target_profile(&ctx, target_user_id).await;
+    let embed = moderation_action_embed(
+        &target_profile,
+        target_user_id,
+        "unbanned",
+        reason.as_deref(),
+        None,
+        config.embed_color,
+    )?;
     http.create_message(msg.channel_id).embeds(&[embed]).await?;
 
     Ok(())
@@ -1,76 +1,85 @@
-use tracing::error;
+use anyhow::Context as _;
 use twilight_http::request::AuditLogReason as _;
 use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
 
 use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::middleware::message_guild_id;
 use crate::commands::moderation::embeds::{fetch_target_profile, moderation_action_embed};
+use crate::commands::registry::command;
 use crate::context::Context;
-use crate::util::parse::parse_target_user_id;
-use crate::util::permissions::has_message_permission;
+use crate::database::guild_config::guild_config;
+use crate::error::BotError;
+use crate::util::permissions::is_timed_out;
 
 pub const META: CommandMeta = CommandMeta {
     name: "untimeout",
     desc: "Remove timeout from a user.",
     category: "moderation",
-    usage: "!untimeout <user> [reason]",
+    usage: "!untimeout <member> [reason]",
+    aliases: &["uto"],
+    cooldown: None,
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MODERATE_MEMBERS,
+    subcommands: &[],
 };
 
+command!(UntimeoutCommand => run);
+
 /// Remove an active timeout from a target user.
-pub async fn run(
-    ctx: Context,
-    msg: Box<MessageCreate>,
-    arg1: Option<&str>,
-    arg_tail: Option<&str>,
-) -> anyhow::Result<()> {
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
     let http = &ctx.http;
-    let Some(guild_id) = msg.guild_id else {
-        http.create_message(msg.channel_id)
-            .content("This command only works in servers.")
-            .await?;
-        return Ok(());
-    };
+    let guild_id = message_guild_id(&msg)?;
 
-    if !has_message_permission(http, &msg, Permissions::MODERATE_MEMBERS).await? {
+    let mut args = args.parser(&ctx, &msg);
+    let (target_user_id, _member) = args.member("member").await?;
+    let reason = args.optional(|args| args.rest("reason"));
+
+    // Ask Discord, since the cache may have missed a timeout set or expired elsewhere
+    let member = http
+        .guild_member(guild_id, target_user_id)
+        .await
+        .map_err(BotError::from)?
+        .model()
+        .await?;
+    ctx.cache.insert_member(guild_id, &member).await;
+
+    if !is_timed_out(member.communication_disabled_until) {
         http.create_message(msg.channel_id)
-            .content("You are not permitted to use this command.")
+            .content("That member isn't timed out.")
             .await?;
         return Ok(());
     }
 
-    let Some(raw_target) = arg1 else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
-
-    let Some(target_user_id) = parse_target_user_id(raw_target) else {
-        let usage = format!("Usage: `{}`", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
-
     let mut request = http
         .update_guild_member(guild_id, target_user_id)
         .communication_disabled_until(None);
-    if let Some(reason) = arg_tail {
+    if let Some(reason) = &reason {
         request = request.reason(reason);
     }
 
-    if let Err(source) = request.await {
-        error!(?source, "untimeout request failed");
-        http.create_message(msg.channel_id)
-            .content("I couldn't remove timeout from that user. Check permissions.")
-            .await?;
-        return Ok(());
-    }
+    let member = request
+        .await
+        .map_err(|source| {
+            BotError::from(source).with_permission_hint(
+                "I need the Timeout Members permission and a role above theirs.",
+            )
+        })
+        .context("untimeout request failed")?
+        .model()
+        .await?;
+    ctx.cache.insert_member(guild_id, &member).await;
 
-    let target_profile = fetch_target_profile(http, target_user_id).await;
+    let config = guild_config(Some(guild_id.get())).await;
+    let target_profile = fetch_target_profile(&ctx, target_user_id).await;
     let embed = moderation_action_embed(
         &target_profile,
         target_user_id,
         "untimed out",
-        arg_tail,
+        reason.as_deref(),
         None,
+        config.embed_color,
     )?;
     http.create_message(msg.channel_id).embeds(&[embed]).await?;
 
@@ -1,64 +1,74 @@
 use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
 
-use crate::commands::CommandMeta;
+use crate::commands::args::{ArgError, Args, CommandArgs};
+use crate::commands::middleware::message_guild_id;
 use crate::commands::moderation::embeds::{
-    fetch_target_profile, guild_only_message, permission_denied_message, usage_message,
-    warnings_overview_embed, warnings_window_label_days,
+    fetch_target_profile, warnings_overview_embed, warnings_window_label_days,
 };
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown};
 use crate::context::Context;
-use crate::database::warnings::{now_unix_secs, warnings_since};
-use crate::util::parse::parse_target_user_id;
-use crate::util::permissions::has_message_permission;
+use crate::database::guild_config::{MAX_WARNING_DAYS, guild_config};
+use crate::database::warnings::{clear_warnings, now_unix_secs, warnings_since};
 
 pub const META: CommandMeta = CommandMeta {
     name: "warnings",
     desc: "Show warning history for a user in a time window.",
     category: "moderation",
-    usage: "!warnings <user> [days|all]",
+    usage: "!warnings <user> [days|all] | !warnings clear <user>",
+    aliases: &["warns"],
+    cooldown: Some(Cooldown::per_user(3)),
+    guild_only: true,
+    owner_only: false,
+    permissions: Permissions::MANAGE_MESSAGES,
+    subcommands: &[CLEAR_META],
 };
 
-const DEFAULT_DAYS: u64 = 30;
+const CLEAR_META: CommandMeta = CommandMeta {
+    name: "clear",
+    desc: "Delete every warning of a user.",
+    category: "moderation",
+    usage: "!warnings clear <user>",
+    aliases: &["reset"],
+    cooldown: None,
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::MODERATE_MEMBERS,
+    subcommands: &[],
+};
+
+command!(WarningsCommand => run);
 
 enum WarningWindow {
     Days(u64),
     All,
 }
 
-/// Show warning history for a target user within a selected time window.
-pub async fn run(
-    ctx: Context,
-    msg: Box<MessageCreate>,
-    arg1: Option<&str>,
-    arg_tail: Option<&str>,
-) -> anyhow::Result<()> {
+/// Show warning history for a target user within a selected time window, or clear it.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
     let http = &ctx.http;
-    let Some(_guild_id) = msg.guild_id else {
-        http.create_message(msg.channel_id)
-            .content(guild_only_message())
-            .await?;
-        return Ok(());
-    };
-
-    if !has_message_permission(http, &msg, Permissions::MANAGE_MESSAGES).await? {
-        http.create_message(msg.channel_id)
-            .content(permission_denied_message())
-            .await?;
+    let guild_id = message_guild_id(&msg)?;
+
+    let config = guild_config(Some(guild_id.get())).await;
+    let clear = args.subcommand_path() == [CLEAR_META.name];
+    let mut args = args.parser(&ctx, &msg);
+    let target_user_id = args.user("user")?;
+
+    if clear {
+        let cleared = clear_warnings(guild_id.get(), target_user_id.get()).await;
+        let name = fetch_target_profile(&ctx, target_user_id)
+            .await
+            .display_name;
+        let out = match cleared {
+            0 => format!("**{}** has no warnings.", name),
+            1 => format!("Cleared 1 warning of **{}**.", name),
+            count => format!("Cleared {} warnings of **{}**.", count, name),
+        };
+        http.create_message(msg.channel_id).content(&out).await?;
         return Ok(());
     }
 
-    let Some(raw_target) = arg1 else {
-        let usage = usage_message(META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
-
-    let Some(target_user_id) = parse_target_user_id(raw_target) else {
-        let usage = usage_message(META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
-
-    let window = parse_window(arg_tail);
+    let window = parse_window(&mut args, config.warning_days)?;
     let (since, window_label) = match window {
         WarningWindow::Days(days) => (
             now_unix_secs().saturating_sub(days.saturating_mul(86_400)),
@@ -67,27 +77,30 @@ pub async fn run(
         WarningWindow::All => (0, "all time".to_owned()),
     };
 
-    let entries = warnings_since(target_user_id.get(), since).await;
-    let target_profile = fetch_target_profile(http, target_user_id).await;
-    let embed = warnings_overview_embed(&target_profile, &window_label, &entries)?;
+    let entries = warnings_since(guild_id.get(), target_user_id.get(), since).await;
+    let target_profile = fetch_target_profile(&ctx, target_user_id).await;
+    let embed =
+        warnings_overview_embed(&target_profile, &window_label, &entries, config.embed_color)?;
 
     http.create_message(msg.channel_id).embeds(&[embed]).await?;
 
     Ok(())
 }
 
-fn parse_window(arg_tail: Option<&str>) -> WarningWindow {
-    let Some(raw) = arg_tail.and_then(|value| value.split_whitespace().next()) else {
-        return WarningWindow::Days(DEFAULT_DAYS);
-    };
-
-    if raw.eq_ignore_ascii_case("all") {
-        return WarningWindow::All;
+/// `all`, a number of days, or the guild's default window when omitted.
+fn parse_window(args: &mut Args<'_>, default_days: u64) -> Result<WarningWindow, ArgError> {
+    if args
+        .optional(|args| args.choice("days", &["all"]))
+        .is_some()
+    {
+        return Ok(WarningWindow::All);
     }
 
-    let Some(days) = raw.parse::<u64>().ok().filter(|value| *value > 0) else {
-        return WarningWindow::Days(DEFAULT_DAYS);
-    };
+    if args.is_empty() {
+        return Ok(WarningWindow::Days(default_days));
+    }
 
-    WarningWindow::Days(days)
+    Ok(WarningWindow::Days(
+        args.integer("days", 1..=MAX_WARNING_DAYS)?,
+    ))
 }
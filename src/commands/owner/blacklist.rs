@@ -0,0 +1,132 @@
+use tracing::{error, info};
+use twilight_model::{
+    gateway::payload::incoming::MessageCreate,
+    guild::Permissions,
+    id::{Id, marker::GuildMarker},
+};
+
+use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::owner::CATEGORY;
+use crate::commands::registry::command;
+use crate::context::Context;
+use crate::database::blacklist::{
+    BlacklistKind, add_to_blacklist, blacklisted, is_blacklisted, remove_from_blacklist,
+};
+
+pub const META: CommandMeta = CommandMeta {
+    name: "blacklist",
+    desc: "Stop users or servers from using the bot.",
+    category: CATEGORY,
+    usage: "!blacklist list | !blacklist user|guild add|remove <id>...",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: true,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+command!(BlacklistCommand => run);
+
+/// List, add and remove global blacklist entries.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let mut args = args.parser(&ctx, &msg);
+    let kind = match args.choice("list|user|guild", &["list", "user", "guild"])? {
+        "list" => {
+            let out = format!(
+                "Blacklisted users: {}\nBlacklisted guilds: {}",
+                id_list(&blacklisted(BlacklistKind::User).await),
+                id_list(&blacklisted(BlacklistKind::Guild).await)
+            );
+            http.create_message(msg.channel_id).content(&out).await?;
+            return Ok(());
+        }
+        "user" => BlacklistKind::User,
+        _ => BlacklistKind::Guild,
+    };
+
+    let action = args.choice("add|remove", &["add", "remove"])?;
+    let ids: Vec<u64> = match kind {
+        BlacklistKind::User => args
+            .variadic(|args| args.user("id"))?
+            .into_iter()
+            .map(Id::get)
+            .collect(),
+        BlacklistKind::Guild => args
+            .variadic(|args| args.guild("id"))?
+            .into_iter()
+            .map(Id::get)
+            .collect(),
+    };
+
+    let mut lines = Vec::with_capacity(ids.len());
+    for &id in &ids {
+        let line = if action == "remove" {
+            match remove_from_blacklist(kind, id).await {
+                Ok(true) => format!("Removed {} `{}` from the blacklist.", kind.label(), id),
+                Ok(false) => format!("`{}` is not blacklisted.", id),
+                Err(source) => save_failed_line(source, id),
+            }
+        } else if kind == BlacklistKind::User && ctx.config.current().is_owner(Id::new(id)) {
+            format!("`{}` is a bot owner and can't be blacklisted.", id)
+        } else {
+            match add_to_blacklist(kind, id).await {
+                Ok(true) => format!("Blacklisted {} `{}`.", kind.label(), id),
+                Ok(false) => format!("`{}` is already blacklisted.", id),
+                Err(source) => save_failed_line(source, id),
+            }
+        };
+        lines.push(line);
+    }
+    http.create_message(msg.channel_id)
+        .content(&lines.join("\n"))
+        .await?;
+
+    // Leave after replying, in case a guild being blacklisted is this one.
+    if kind == BlacklistKind::Guild && action == "add" {
+        for id in ids {
+            leave_if_blacklisted(&ctx, Id::new(id)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Log a failed blacklist save and describe it; the change was rolled back.
+fn save_failed_line(source: anyhow::Error, id: u64) -> String {
+    error!(?source, id, "failed to update blacklist");
+    format!(
+        "Couldn't save the blacklist file, so `{}` was left unchanged.",
+        id
+    )
+}
+
+/// Leave a guild the bot is in if it is blacklisted.
+///
+/// Runs on every `GuildCreate`, so blacklisted guilds are left again on startup
+/// and whenever they re-invite the bot.
+pub async fn leave_if_blacklisted(ctx: &Context, guild_id: Id<GuildMarker>) -> anyhow::Result<()> {
+    if !is_blacklisted(BlacklistKind::Guild, guild_id.get()).await
+        || ctx.cache.guild(guild_id).await.is_none()
+    {
+        return Ok(());
+    }
+
+    info!(guild = guild_id.get(), "leaving blacklisted guild");
+    ctx.http.leave_guild(guild_id).await?;
+
+    Ok(())
+}
+
+fn id_list(ids: &[u64]) -> String {
+    if ids.is_empty() {
+        return "none".to_owned();
+    }
+
+    ids.iter()
+        .map(|id| format!("`{}`", id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use twilight_model::{
+    gateway::payload::incoming::{InteractionCreate, MessageCreate},
+    guild::Permissions,
+};
+
+use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::owner::CATEGORY;
+use crate::commands::registry::command;
+use crate::context::Context;
+use crate::database::guild_config::guild_config;
+use crate::util::pagination::{
+    PaginationInteractionValidation, PaginationModalSubmitValidation, build_paginated_list_view,
+    clamp_page, open_jump_modal_from_token, resolve_modal_target_page, send_paginated_message,
+    timeout_secs, total_pages, update_paginated_interaction_message,
+    validate_interaction_for_command, validate_jump_modal_for_command,
+};
+
+pub const META: CommandMeta = CommandMeta {
+    name: "guilds",
+    desc: "List the servers the bot is in.",
+    category: CATEGORY,
+    usage: "!guilds [page]",
+    aliases: &["servers"],
+    cooldown: None,
+    guild_only: false,
+    owner_only: true,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+command!(GuildsCommand => run, paginated);
+
+const GUILDS_PER_PAGE: usize = 15;
+
+/// List every joined guild with its ID and member count.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let requested_page = args.parser(&ctx, &msg).page()?;
+
+    let items = guild_items(&ctx).await;
+    if items.is_empty() {
+        http.create_message(msg.channel_id)
+            .content("The bot is not in any servers yet.")
+            .await?;
+        return Ok(());
+    }
+
+    let total = total_pages(items.len(), GUILDS_PER_PAGE);
+    if requested_page > total {
+        let out = format!(
+            "Page {} does not exist. Available pages: 1-{}.",
+            requested_page, total
+        );
+        http.create_message(msg.channel_id).content(&out).await?;
+        return Ok(());
+    }
+
+    let embed_color = guild_config(msg.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
+    let (embed, components) = build_paginated_list_view(
+        META.name,
+        &guilds_title(items.len()),
+        embed_color,
+        &items,
+        requested_page,
+        GUILDS_PER_PAGE,
+        msg.author.id.get(),
+        timeout_secs(),
+    )?;
+
+    send_paginated_message(
+        Arc::clone(&ctx.http),
+        msg.channel_id,
+        embed,
+        components,
+        total,
+        timeout_secs(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Handle pagination button presses for the `guilds` command.
+pub async fn handle_pagination_interaction(
+    ctx: Context,
+    interaction: Box<InteractionCreate>,
+) -> anyhow::Result<bool> {
+    let http = &ctx.http;
+    let (actor_id, token) =
+        match validate_interaction_for_command(http, &interaction, META.name).await? {
+            PaginationInteractionValidation::NotForCommand => return Ok(false),
+            PaginationInteractionValidation::HandledInvalid => return Ok(true),
+            PaginationInteractionValidation::Valid {
+                actor_user_id,
+                token,
+            } => (actor_user_id, token),
+        };
+
+    let items = guild_items(&ctx).await;
+    let total = total_pages(items.len(), GUILDS_PER_PAGE);
+
+    if token.action == "jump" {
+        open_jump_modal_from_token(http, &interaction, &token, total).await?;
+        return Ok(true);
+    }
+
+    let target_page = clamp_page(token.page, total);
+
+    let embed_color = guild_config(interaction.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
+    let (embed, components) = build_paginated_list_view(
+        META.name,
+        &guilds_title(items.len()),
+        embed_color,
+        &items,
+        target_page,
+        GUILDS_PER_PAGE,
+        actor_id,
+        timeout_secs(),
+    )?;
+
+    update_paginated_interaction_message(
+        Arc::clone(&ctx.http),
+        &interaction,
+        embed,
+        components,
+        total,
+        timeout_secs(),
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// Handle jump-modal submit interactions for the `guilds` command.
+pub async fn handle_pagination_modal_interaction(
+    ctx: Context,
+    interaction: Box<InteractionCreate>,
+) -> anyhow::Result<bool> {
+    let http = &ctx.http;
+    let (actor_id, entered_page, total_pages_hint) =
+        match validate_jump_modal_for_command(http, &interaction, META.name).await? {
+            PaginationModalSubmitValidation::NotForCommand => return Ok(false),
+            PaginationModalSubmitValidation::HandledInvalid => return Ok(true),
+            PaginationModalSubmitValidation::Valid {
+                actor_user_id,
+                requested_page,
+                total_pages_hint,
+                ..
+            } => (actor_user_id, requested_page, total_pages_hint),
+        };
+
+    let items = guild_items(&ctx).await;
+    let total = total_pages(items.len(), GUILDS_PER_PAGE);
+    let target_page = resolve_modal_target_page(entered_page, total, total_pages_hint);
+
+    let embed_color = guild_config(interaction.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
+    let (embed, components) = build_paginated_list_view(
+        META.name,
+        &guilds_title(items.len()),
+        embed_color,
+        &items,
+        target_page,
+        GUILDS_PER_PAGE,
+        actor_id,
+        timeout_secs(),
+    )?;
+
+    update_paginated_interaction_message(
+        Arc::clone(&ctx.http),
+        &interaction,
+        embed,
+        components,
+        total,
+        timeout_secs(),
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// One line per cached guild, sorted by name.
+async fn guild_items(ctx: &Context) -> Vec<String> {
+    let mut guilds = ctx.cache.guilds().await;
+    guilds.sort_by_cached_key(|(id, guild)| (guild.name.to_lowercase(), *id));
+
+    guilds
+        .into_iter()
+        .map(|(id, guild)| match guild.member_count {
+            Some(count) => format!("**{}** (`{}`) · {} members", guild.name, id, count),
+            None => format!("**{}** (`{}`)", guild.name, id),
+        })
+        .collect()
+}
+
+fn guilds_title(count: usize) -> String {
+    format!("Servers ({})", count)
+}
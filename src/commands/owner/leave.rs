@@ -0,0 +1,41 @@
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
+
+use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::owner::CATEGORY;
+use crate::commands::registry::command;
+use crate::context::Context;
+
+pub const META: CommandMeta = CommandMeta {
+    name: "leave",
+    desc: "Make the bot leave a server.",
+    category: CATEGORY,
+    usage: "!leave <guild_id>",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: true,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+command!(LeaveCommand => run);
+
+/// Leave a guild the bot is in.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    let guild_id = args.parser(&ctx, &msg).guild("guild_id")?;
+
+    let Some(guild) = ctx.cache.guild(guild_id).await else {
+        let out = format!("The bot is not in a server with ID `{}`.", guild_id);
+        http.create_message(msg.channel_id).content(&out).await?;
+        return Ok(());
+    };
+
+    // Confirm first; the channel is gone once the invoking guild is left.
+    let out = format!("Leaving **{}** (`{}`).", guild.name, guild_id);
+    http.create_message(msg.channel_id).content(&out).await?;
+    http.leave_guild(guild_id).await?;
+
+    Ok(())
+}
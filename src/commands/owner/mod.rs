@@ -0,0 +1,9 @@
+pub mod blacklist;
+pub mod guilds;
+pub mod leave;
+pub mod reload;
+pub mod shutdown;
+pub mod status;
+
+/// Category shared by every owner command; hidden from `help` for everyone else.
+pub const CATEGORY: &str = "owner";
@@ -0,0 +1,40 @@
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
+
+use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::owner::CATEGORY;
+use crate::commands::registry::command;
+use crate::context::Context;
+
+pub const META: CommandMeta = CommandMeta {
+    name: "reload",
+    desc: "Reload the bot's config file without restarting.",
+    category: CATEGORY,
+    usage: "!reload config",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: true,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+command!(ReloadCommand => run);
+
+/// Re-read the config file and report which settings changed.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
+    let http = &ctx.http;
+    args.parser(&ctx, &msg).choice("target", &["config"])?;
+
+    let out = match ctx.config.reload() {
+        Ok(changes) if changes.is_empty() => "Config reloaded, nothing changed.".to_owned(),
+        Ok(changes) => format!("Config reloaded:\n{}", changes.join("\n")),
+        Err(error) => format!(
+            "Config reload failed, keeping the current config:\n{:#}",
+            error
+        ),
+    };
+    http.create_message(msg.channel_id).content(&out).await?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
+
+use crate::commands::CommandMeta;
+use crate::commands::owner::CATEGORY;
+use crate::commands::registry::command;
+use crate::context::Context;
+
+pub const META: CommandMeta = CommandMeta {
+    name: "shutdown",
+    desc: "Shut the bot down cleanly.",
+    category: CATEGORY,
+    usage: "!shutdown",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: true,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+command!(ShutdownCommand => |ctx, msg, _args| run(ctx, msg));
+
+/// Start the same clean shutdown as SIGTERM.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>) -> anyhow::Result<()> {
+    ctx.http
+        .create_message(msg.channel_id)
+        .content("Shutting down...")
+        .await?;
+    ctx.shutdown.notify_one();
+
+    Ok(())
+}
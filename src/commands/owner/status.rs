@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
+
+use crate::commands::CommandMeta;
+use crate::commands::owner::CATEGORY;
+use crate::commands::registry::command;
+use crate::context::Context;
+use crate::database::command_stats::command_stats;
+use crate::shards::state_label;
+
+pub const META: CommandMeta = CommandMeta {
+    name: "status",
+    desc: "Show uptime, memory use, task counts, shard state and command usage.",
+    category: CATEGORY,
+    usage: "!status",
+    aliases: &[],
+    cooldown: None,
+    guild_only: false,
+    owner_only: true,
+    permissions: Permissions::empty(),
+    subcommands: &[],
+};
+
+/// Most commands listed under the command totals.
+const BUSIEST_COMMANDS: usize = 5;
+
+command!(StatusCommand => |ctx, msg, _args| run(ctx, msg));
+
+/// Report process and gateway health.
+pub async fn run(ctx: Context, msg: Box<MessageCreate>) -> anyhow::Result<()> {
+    let metrics = tokio::runtime::Handle::current().metrics();
+    let memory = resident_memory_bytes().map_or_else(
+        || "unknown".to_owned(),
+        |bytes| format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)),
+    );
+
+    let shards = ctx
+        .shards
+        .statuses()
+        .await
+        .into_iter()
+        .map(|(number, status)| {
+            let latency = status.latency.map_or_else(
+                || "n/a".to_owned(),
+                |latency| format!("{}ms", latency.as_millis()),
+            );
+            format!("• {}: {} ({})", number, state_label(status.state), latency)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let stats = command_stats().await;
+    let runs: u64 = stats.iter().map(|(_, stats)| stats.runs).sum();
+    let failures: u64 = stats.iter().map(|(_, stats)| stats.failures).sum();
+    let busiest = stats
+        .iter()
+        .take(BUSIEST_COMMANDS)
+        .map(|(name, stats)| {
+            format!(
+                "• {}: {} run(s), {} failed, {}ms avg",
+                name,
+                stats.runs,
+                stats.failures,
+                stats.average_time().as_millis()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut out = format!(
+        "Uptime: {}\nMemory: {}\nTasks: {} alive on {} worker thread(s)\nGuilds: {}\nShards:\n{}\nCommands: {} run(s), {} failed",
+        format_uptime(ctx.started_at.elapsed()),
+        memory,
+        metrics.num_alive_tasks(),
+        metrics.num_workers(),
+        ctx.cache.guilds().await.len(),
+        shards,
+        runs,
+        failures
+    );
+    if !busiest.is_empty() {
+        out.push_str(&format!("\n{}", busiest.join("\n")));
+    }
+    ctx.http
+        .create_message(msg.channel_id)
+        .content(&out)
+        .await?;
+
+    Ok(())
+}
+
+/// Resident set size of this process, where the platform exposes it.
+fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kib = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some(kib * 1024)
+}
+
+/// Render an uptime as `1d 2h 3m 4s`, leaving out leading zero units.
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let parts = [
+        (secs / 86_400, 'd'),
+        (secs / 3_600 % 24, 'h'),
+        (secs / 60 % 60, 'm'),
+        (secs % 60, 's'),
+    ];
+
+    let out = parts
+        .into_iter()
+        .skip_while(|(value, unit)| *value == 0 && *unit != 's')
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect::<Vec<_>>();
+
+    out.join(" ")
+}
@@ -0,0 +1,266 @@
+//! The command list and the trait every command implements.
+//!
+//! Dispatch, `help`, `usage` and interaction routing all read from
+//! [`registry`], so adding a command is one line in [`CommandRegistry::build`].
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    future::Future,
+    pin::Pin,
+    sync::LazyLock,
+};
+
+use twilight_model::gateway::payload::incoming::{InteractionCreate, MessageCreate};
+
+use crate::commands::args::CommandArgs;
+use crate::commands::{CommandMeta, admin, moderation, owner, utility};
+use crate::context::Context;
+use crate::util::parse::edit_distance;
+
+/// Most names [`CommandRegistry::suggest`] returns.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Boxed future returned by [`Command`] methods so commands can live in one list.
+pub type CommandFuture<T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send>>;
+
+/// A text command and, for paginated commands, its component handlers.
+pub trait Command: Send + Sync {
+    fn meta(&self) -> &'static CommandMeta;
+
+    fn run(&self, ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> CommandFuture<()>;
+
+    /// Handle a button press on one of this command's messages.
+    ///
+    /// Resolves to `false` when the interaction wasn't for this command.
+    fn handle_component(
+        &self,
+        _ctx: Context,
+        _interaction: Box<InteractionCreate>,
+    ) -> CommandFuture<bool> {
+        Box::pin(async { Ok(false) })
+    }
+
+    /// Handle a modal submitted from one of this command's messages.
+    fn handle_modal(
+        &self,
+        _ctx: Context,
+        _interaction: Box<InteractionCreate>,
+    ) -> CommandFuture<bool> {
+        Box::pin(async { Ok(false) })
+    }
+}
+
+/// Declare a command's unit struct and its [`Command`] impl from the module's `META`.
+///
+/// `command!(PingCommand => run)` calls `run(ctx, msg, args)`; a closure-like
+/// `|ctx, msg, args| expr` adapts any other signature. A trailing `paginated`
+/// routes buttons and modals to the module's `handle_pagination_interaction`
+/// and `handle_pagination_modal_interaction`.
+macro_rules! command {
+    ($command:ident => |$ctx:ident, $msg:ident, $args:ident| $run:expr $(, $paginated:ident)?) => {
+        pub struct $command;
+
+        impl $crate::commands::registry::Command for $command {
+            fn meta(&self) -> &'static $crate::commands::CommandMeta {
+                &META
+            }
+
+            fn run(
+                &self,
+                $ctx: $crate::context::Context,
+                $msg: Box<twilight_model::gateway::payload::incoming::MessageCreate>,
+                $args: $crate::commands::args::CommandArgs,
+            ) -> $crate::commands::registry::CommandFuture<()> {
+                Box::pin(async move { $run.await })
+            }
+
+            $($crate::commands::registry::command!(@$paginated);)?
+        }
+    };
+    ($command:ident => $run:path $(, $paginated:ident)?) => {
+        $crate::commands::registry::command!(
+            $command => |ctx, msg, args| $run(ctx, msg, args) $(, $paginated)?
+        );
+    };
+    (@paginated) => {
+        fn handle_component(
+            &self,
+            ctx: $crate::context::Context,
+            interaction: Box<twilight_model::gateway::payload::incoming::InteractionCreate>,
+        ) -> $crate::commands::registry::CommandFuture<bool> {
+            Box::pin(handle_pagination_interaction(ctx, interaction))
+        }
+
+        fn handle_modal(
+            &self,
+            ctx: $crate::context::Context,
+            interaction: Box<twilight_model::gateway::payload::incoming::InteractionCreate>,
+        ) -> $crate::commands::registry::CommandFuture<bool> {
+            Box::pin(handle_pagination_modal_interaction(ctx, interaction))
+        }
+    };
+}
+
+pub(crate) use command;
+
+/// Which handler an interaction custom ID belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionKind {
+    Component,
+    Modal,
+}
+
+/// Every command, indexed by name and built-in alias.
+pub struct CommandRegistry {
+    commands: Vec<&'static dyn Command>,
+    by_name: HashMap<&'static str, usize>,
+}
+
+static REGISTRY: LazyLock<CommandRegistry> = LazyLock::new(CommandRegistry::build);
+
+/// The bot's command registry.
+pub fn registry() -> &'static CommandRegistry {
+    &REGISTRY
+}
+
+impl CommandRegistry {
+    fn build() -> Self {
+        let commands: Vec<&'static dyn Command> = vec![
+            &utility::ping::PingCommand,
+            &utility::universe::UniverseCommand,
+            &utility::help::HelpCommand,
+            &utility::usage::UsageCommand,
+            &utility::pagetest::PagetestCommand,
+            &moderation::ban::BanCommand,
+            &moderation::unban::UnbanCommand,
+            &moderation::kick::KickCommand,
+            &moderation::timeout::TimeoutCommand,
+            &moderation::untimeout::UntimeoutCommand,
+            &moderation::warn::WarnCommand,
+            &moderation::warnings::WarningsCommand,
+            &moderation::purge::PurgeCommand,
+            &moderation::permissions::PermissionsCommand,
+            &moderation::audit::AuditCommand,
+            &moderation::roles::RolesCommand,
+            &admin::config::ConfigCommand,
+            &admin::alias::AliasCommand,
+            &admin::command::CommandCommand,
+            &admin::access::AccessCommand,
+            &owner::reload::ReloadCommand,
+            &owner::guilds::GuildsCommand,
+            &owner::leave::LeaveCommand,
+            &owner::shutdown::ShutdownCommand,
+            &owner::status::StatusCommand,
+            &owner::blacklist::BlacklistCommand,
+            // Add new commands here
+        ];
+
+        let mut by_name = HashMap::new();
+        for (index, command) in commands.iter().enumerate() {
+            let meta = command.meta();
+            for name in std::iter::once(&meta.name).chain(meta.aliases) {
+                if let Some(previous) = by_name.insert(*name, index) {
+                    panic!(
+                        "`{}` is claimed by both `{}` and `{}`",
+                        name,
+                        commands[previous].meta().name,
+                        meta.name
+                    );
+                }
+            }
+        }
+
+        Self { commands, by_name }
+    }
+
+    /// All commands in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &'static dyn Command> + '_ {
+        self.commands.iter().copied()
+    }
+
+    /// Resolve a command name, built-in alias or custom guild alias.
+    ///
+    /// Built-in names and aliases always win over custom aliases.
+    pub fn find(
+        &self,
+        name: &str,
+        guild_aliases: &BTreeMap<String, String>,
+    ) -> Option<&'static dyn Command> {
+        let index = self.by_name.get(name).or_else(|| {
+            let target = guild_aliases.get(name)?;
+            self.by_name.get(target.as_str())
+        })?;
+
+        Some(self.commands[*index])
+    }
+
+    /// Names and aliases close to a mistyped command name, closest first.
+    ///
+    /// Names under three characters are skipped since nearly anything is one
+    /// edit away from them, and each command is suggested at most once.
+    pub fn suggest(
+        &self,
+        typed: &str,
+        guild_aliases: &BTreeMap<String, String>,
+        show_owner: bool,
+    ) -> Vec<String> {
+        let max_distance = (typed.chars().count() / 3).clamp(1, 2);
+        let builtin = self.by_name.iter().map(|(name, index)| (*name, *index));
+        let custom = guild_aliases.iter().filter_map(|(alias, target)| {
+            Some((alias.as_str(), *self.by_name.get(target.as_str())?))
+        });
+
+        let mut candidates: Vec<(usize, &str, usize)> = builtin
+            .chain(custom)
+            .filter(|(name, _)| name.chars().count() >= 3)
+            .filter(|(_, index)| show_owner || !self.commands[*index].meta().owner_only)
+            .map(|(name, index)| (edit_distance(typed, name), name, index))
+            .filter(|(distance, _, _)| *distance <= max_distance)
+            .collect();
+        candidates.sort_unstable();
+
+        let mut seen = HashSet::new();
+        candidates
+            .into_iter()
+            .filter(|(_, _, index)| seen.insert(*index))
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, name, _)| name.to_owned())
+            .collect()
+    }
+
+    /// The command owning a pagination custom ID (`pg:<command>…` or `pgm:<command>…`).
+    pub fn route_interaction(
+        &self,
+        custom_id: &str,
+    ) -> Option<(&'static dyn Command, InteractionKind)> {
+        let (kind, rest) = match custom_id.strip_prefix("pgm:") {
+            Some(rest) => (InteractionKind::Modal, rest),
+            None => (InteractionKind::Component, custom_id.strip_prefix("pg:")?),
+        };
+
+        // Commands may encode state after a `|`, e.g. `help|utility`.
+        let name = rest.split([':', '|']).next()?;
+        let index = self.by_name.get(name)?;
+
+        Some((self.commands[*index], kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_accepts_every_command_name_and_alias() {
+        let registry = CommandRegistry::build();
+        let none = BTreeMap::new();
+
+        for command in registry.iter() {
+            let meta = command.meta();
+            for name in std::iter::once(&meta.name).chain(meta.aliases) {
+                let found = registry.find(name, &none).map(|found| found.meta().name);
+                assert_eq!(found, Some(meta.name), "`{}` resolves elsewhere", name);
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use crate::commands::CommandMeta;
+use crate::util::permissions::permission_names;
 
 pub fn unknown_category_message(wanted_category: &str, valid_categories: &[&str]) -> String {
     let valid = valid_categories
@@ -28,7 +31,11 @@ pub fn page_out_of_range_message(requested_page: usize, total_pages: usize) -> S
     )
 }
 
-pub fn grouped_help_description(commands: &[&CommandMeta]) -> String {
+pub fn grouped_help_description(
+    commands: &[&CommandMeta],
+    prefix: &str,
+    guild_aliases: &BTreeMap<String, String>,
+) -> String {
     let mut out = String::new();
     let mut current_category: Option<&str> = None;
 
@@ -41,7 +48,18 @@ pub fn grouped_help_description(commands: &[&CommandMeta]) -> String {
             current_category = Some(command.category);
         }
 
-        out.push_str(&format!("`{}`: {}\n", command.name, command.desc));
+        let aliases = command.all_aliases(guild_aliases);
+        if aliases.is_empty() {
+            out.push_str(&format!("`{}{}`: {}\n", prefix, command.name, command.desc));
+        } else {
+            out.push_str(&format!(
+                "`{}{}` ({}): {}\n",
+                prefix,
+                command.name,
+                aliases.join(", "),
+                command.desc
+            ));
+        }
     }
 
     if out.is_empty() {
@@ -51,6 +69,65 @@ pub fn grouped_help_description(commands: &[&CommandMeta]) -> String {
     out.trim_end().to_owned()
 }
 
+/// Description for `!help <command>`: usage, aliases, limits and the subcommand tree.
+pub fn command_help_description(command: &CommandMeta, aliases: &[&str], prefix: &str) -> String {
+    let mut out = format!(
+        "{}\n\nUsage: `{}`",
+        command.desc,
+        command.usage_with_prefix(prefix)
+    );
+
+    if !aliases.is_empty() {
+        out.push_str(&format!("\nAliases: {}", aliases.join(", ")));
+    }
+    if let Some(cooldown) = command.cooldown {
+        out.push_str(&format!("\nCooldown: {}", cooldown.describe()));
+    }
+    if !command.permissions.is_empty() {
+        out.push_str(&format!(
+            "\nRequires: {}",
+            permission_names(command.permissions).join(", ")
+        ));
+    }
+
+    if !command.subcommands.is_empty() {
+        out.push_str("\n\n**Subcommands**\n");
+        push_subcommand_tree(&mut out, command.subcommands, prefix, "");
+    }
+
+    out.trim_end().to_owned()
+}
+
+/// One line per subcommand, drawn as a tree and indented per nesting level.
+fn push_subcommand_tree(out: &mut String, subcommands: &[CommandMeta], prefix: &str, indent: &str) {
+    for (index, sub) in subcommands.iter().enumerate() {
+        let is_last = index + 1 == subcommands.len();
+        let branch = if is_last { "└─" } else { "├─" };
+        out.push_str(&format!(
+            "{}{} `{}`: {}",
+            indent,
+            branch,
+            sub.usage_with_prefix(prefix),
+            sub.desc
+        ));
+        if !sub.permissions.is_empty() {
+            out.push_str(&format!(
+                " (requires {})",
+                permission_names(sub.permissions).join(", ")
+            ));
+        }
+        out.push('\n');
+
+        // Em spaces, since Discord trims runs of regular spaces at a line start.
+        let child_indent = if is_last {
+            format!("{}\u{2003}\u{2003}", indent)
+        } else {
+            format!("{}│\u{2003}", indent)
+        };
+        push_subcommand_tree(out, sub.subcommands, prefix, &child_indent);
+    }
+}
+
 fn display_category(category: &str) -> String {
     let mut chars = category.chars();
     match chars.next() {
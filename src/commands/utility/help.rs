@@ -1,17 +1,26 @@
-use std::sync::Arc;
-use twilight_model::gateway::payload::incoming::{InteractionCreate, MessageCreate};
+use std::{collections::BTreeMap, sync::Arc};
 
+use twilight_model::{
+    gateway::payload::incoming::{InteractionCreate, MessageCreate},
+    guild::Permissions,
+    id::Id,
+};
+use twilight_util::builder::embed::EmbedBuilder;
+
+use crate::commands::registry::{command, registry};
 use crate::commands::utility::embeds::{
-    grouped_help_description, no_commands_message, page_out_of_range_message,
-    unknown_category_message,
+    command_help_description, grouped_help_description, no_commands_message,
+    page_out_of_range_message, unknown_category_message,
 };
-use crate::commands::{COMMANDS, CommandMeta};
+use crate::commands::{CommandMeta, Cooldown, find_command, owner, qualified_name};
 use crate::context::Context;
+use crate::database::aliases::guild_aliases;
+use crate::database::guild_config::guild_config;
 use crate::util::pagination::{
-    DEFAULT_TIMEOUT_SECS, PaginationInteractionValidation, PaginationModalSubmitValidation,
-    build_paginated_view, build_paginated_view_with_footer_note, clamp_page,
-    open_jump_modal_from_token, page_window, resolve_modal_target_page, send_paginated_message,
-    total_pages, update_paginated_interaction_message, validate_interaction_for_command_prefix,
+    PaginationInteractionValidation, PaginationModalSubmitValidation, build_paginated_view,
+    build_paginated_view_with_footer_note, clamp_page, open_jump_modal_from_token, page_window,
+    resolve_modal_target_page, send_paginated_message, timeout_secs, total_pages,
+    update_paginated_interaction_message, validate_interaction_for_command_prefix,
     validate_jump_modal_for_command_prefix,
 };
 
@@ -19,13 +28,27 @@ pub const META: CommandMeta = CommandMeta {
     name: "help",
     desc: "Lists out all available commands.",
     category: "utility",
-    usage: "!help [page|category]",
+    usage: "!help [page|category] | !help <command> [subcommand...]",
+    aliases: &["h", "commands"],
+    cooldown: Some(Cooldown::per_user(5)),
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
 };
 
+command!(HelpCommand => |ctx, msg, args| run(ctx, msg, args.arg1(), args.arg_tail()), paginated);
+
 const HELP_COMMANDS_PER_PAGE: usize = 20;
 
-/// Render the command catalog, optionally filtered by category or page.
-pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> anyhow::Result<()> {
+/// Render the command catalog, optionally filtered by category or page, or
+/// describe one command and its subcommands.
+pub async fn run(
+    ctx: Context,
+    msg: Box<MessageCreate>,
+    arg1: Option<&str>,
+    arg_tail: Option<&str>,
+) -> anyhow::Result<()> {
     let http = &ctx.http;
     let parsed_page = arg1.and_then(|raw| raw.parse::<usize>().ok().filter(|page| *page >= 1));
     let category = match (arg1, parsed_page) {
@@ -33,19 +56,31 @@ pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> a
         _ => None,
     };
 
-    let mut categories: Vec<&str> = COMMANDS.iter().map(|c| c.category).collect();
+    let show_owner = ctx.config.current().is_owner(msg.author.id);
+    let mut categories: Vec<&str> = registry()
+        .iter()
+        .map(|command| command.meta().category)
+        .filter(|category| show_owner || *category != owner::CATEGORY)
+        .collect();
     categories.sort_unstable();
     categories.dedup();
 
     if let Some(wanted_category) = category
         && !categories.contains(&wanted_category)
     {
+        let aliases = guild_aliases(msg.guild_id.map(|id| id.get())).await;
+        if let Some(command) = find_command(&wanted_category.to_ascii_lowercase(), &aliases)
+            .filter(|command| show_owner || !command.owner_only)
+        {
+            return send_command_help(&ctx, &msg, command, &aliases, arg_tail).await;
+        }
+
         let out = unknown_category_message(wanted_category, &categories);
         http.create_message(msg.channel_id).content(&out).await?;
         return Ok(());
     }
 
-    let commands = sorted_commands(category);
+    let commands = sorted_commands(category, show_owner);
     if commands.is_empty() {
         let out = no_commands_message(category);
         http.create_message(msg.channel_id).content(&out).await?;
@@ -61,31 +96,36 @@ pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> a
         return Ok(());
     }
 
+    let config = guild_config(msg.guild_id.map(|id| id.get())).await;
+    let prefix = config.prefix;
+    let aliases = guild_aliases(msg.guild_id.map(|id| id.get())).await;
     let (start, end) = page_window(commands.len(), HELP_COMMANDS_PER_PAGE, requested_page);
-    let description = grouped_help_description(&commands[start..end]);
+    let description = grouped_help_description(&commands[start..end], &prefix, &aliases);
     let pagination_command = help_pagination_command(category);
     let title = help_title();
-    let footer_note = help_footer_note();
+    let footer_note = help_footer_note(&prefix);
 
     let (embed, components) = match footer_note.as_deref() {
         Some(note) => build_paginated_view_with_footer_note(
             &pagination_command,
             &title,
+            config.embed_color,
             description,
             requested_page,
             total,
             msg.author.id.get(),
-            DEFAULT_TIMEOUT_SECS,
+            timeout_secs(),
             Some(note),
         )?,
         None => build_paginated_view(
             &pagination_command,
             &title,
+            config.embed_color,
             description,
             requested_page,
             total,
             msg.author.id.get(),
-            DEFAULT_TIMEOUT_SECS,
+            timeout_secs(),
         )?,
     };
 
@@ -95,13 +135,57 @@ pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> a
         embed,
         components,
         total,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
     Ok(())
 }
 
+/// Describe a command, or the subcommand named by `path`, as an embed.
+async fn send_command_help(
+    ctx: &Context,
+    msg: &MessageCreate,
+    command: &'static CommandMeta,
+    guild_aliases: &BTreeMap<String, String>,
+    path: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut shown = command;
+    let mut names = Vec::new();
+    for word in path.unwrap_or("").split_whitespace() {
+        let Some(sub) = shown.subcommand(word) else {
+            break;
+        };
+        names.push(sub.name);
+        shown = sub;
+    }
+
+    // Custom aliases only ever point at top-level commands.
+    let aliases = if names.is_empty() {
+        command.all_aliases(guild_aliases)
+    } else {
+        shown.aliases.to_vec()
+    };
+
+    let config = guild_config(msg.guild_id.map(|id| id.get())).await;
+    let embed = EmbedBuilder::new()
+        .color(config.embed_color)
+        .title(format!(
+            "{}{}",
+            config.prefix,
+            qualified_name(command, &names)
+        ))
+        .description(command_help_description(shown, &aliases, &config.prefix))
+        .validate()?
+        .build();
+    ctx.http
+        .create_message(msg.channel_id)
+        .embeds(&[embed])
+        .await?;
+
+    Ok(())
+}
+
 /// Handle pagination button presses for the `help` command.
 pub async fn handle_pagination_interaction(
     ctx: Context,
@@ -119,7 +203,8 @@ pub async fn handle_pagination_interaction(
         };
 
     let category = category_from_pagination_command(&token.command);
-    let commands = sorted_commands(category.as_deref());
+    let show_owner = ctx.config.current().is_owner(Id::new(actor_id));
+    let commands = sorted_commands(category.as_deref(), show_owner);
     if commands.is_empty() {
         return Ok(true);
     }
@@ -133,30 +218,35 @@ pub async fn handle_pagination_interaction(
 
     let target_page = clamp_page(token.page, total);
 
+    let config = guild_config(interaction.guild_id.map(|id| id.get())).await;
+    let prefix = config.prefix;
+    let aliases = guild_aliases(interaction.guild_id.map(|id| id.get())).await;
     let (start, end) = page_window(commands.len(), HELP_COMMANDS_PER_PAGE, target_page);
-    let description = grouped_help_description(&commands[start..end]);
+    let description = grouped_help_description(&commands[start..end], &prefix, &aliases);
     let title = help_title();
-    let footer_note = help_footer_note();
+    let footer_note = help_footer_note(&prefix);
 
     let (embed, components) = match footer_note.as_deref() {
         Some(note) => build_paginated_view_with_footer_note(
             &token.command,
             &title,
+            config.embed_color,
             description,
             target_page,
             total,
             actor_id,
-            DEFAULT_TIMEOUT_SECS,
+            timeout_secs(),
             Some(note),
         )?,
         None => build_paginated_view(
             &token.command,
             &title,
+            config.embed_color,
             description,
             target_page,
             total,
             actor_id,
-            DEFAULT_TIMEOUT_SECS,
+            timeout_secs(),
         )?,
     };
 
@@ -166,7 +256,7 @@ pub async fn handle_pagination_interaction(
         embed,
         components,
         total,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
@@ -192,7 +282,8 @@ pub async fn handle_pagination_modal_interaction(
         };
 
     let category = category_from_pagination_command(&command);
-    let commands = sorted_commands(category.as_deref());
+    let show_owner = ctx.config.current().is_owner(Id::new(actor_id));
+    let commands = sorted_commands(category.as_deref(), show_owner);
     if commands.is_empty() {
         return Ok(true);
     }
@@ -200,30 +291,35 @@ pub async fn handle_pagination_modal_interaction(
     let total: usize = total_pages(commands.len(), HELP_COMMANDS_PER_PAGE);
     let target_page = resolve_modal_target_page(entered_page, total, total_pages_hint);
 
+    let config = guild_config(interaction.guild_id.map(|id| id.get())).await;
+    let prefix = config.prefix;
+    let aliases = guild_aliases(interaction.guild_id.map(|id| id.get())).await;
     let (start, end) = page_window(commands.len(), HELP_COMMANDS_PER_PAGE, target_page);
-    let description = grouped_help_description(&commands[start..end]);
+    let description = grouped_help_description(&commands[start..end], &prefix, &aliases);
     let title = help_title();
-    let footer_note = help_footer_note();
+    let footer_note = help_footer_note(&prefix);
 
     let (embed, components) = match footer_note.as_deref() {
         Some(note) => build_paginated_view_with_footer_note(
             &command,
             &title,
+            config.embed_color,
             description,
             target_page,
             total,
             actor_id,
-            DEFAULT_TIMEOUT_SECS,
+            timeout_secs(),
             Some(note),
         )?,
         None => build_paginated_view(
             &command,
             &title,
+            config.embed_color,
             description,
             target_page,
             total,
             actor_id,
-            DEFAULT_TIMEOUT_SECS,
+            timeout_secs(),
         )?,
     };
 
@@ -233,7 +329,7 @@ pub async fn handle_pagination_modal_interaction(
         embed,
         components,
         total,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
@@ -255,13 +351,16 @@ fn help_title() -> String {
     "Available Commands".to_owned()
 }
 
-fn help_footer_note() -> Option<String> {
-    None
+fn help_footer_note(prefix: &str) -> Option<String> {
+    Some(format!("Use {prefix}usage <command> for syntax"))
 }
 
-fn sorted_commands(category: Option<&str>) -> Vec<&'static CommandMeta> {
-    let mut filtered: Vec<&'static CommandMeta> = COMMANDS
+/// Commands to list, leaving out owner commands unless `show_owner` is set.
+fn sorted_commands(category: Option<&str>, show_owner: bool) -> Vec<&'static CommandMeta> {
+    let mut filtered: Vec<&'static CommandMeta> = registry()
         .iter()
+        .map(|command| command.meta())
+        .filter(|cmd| show_owner || !cmd.owner_only)
         .filter(|cmd| match category {
             Some(wanted) => cmd.category == wanted,
             None => true,
@@ -1,15 +1,20 @@
 use std::sync::Arc;
 
-use twilight_model::gateway::payload::incoming::{InteractionCreate, MessageCreate};
+use twilight_model::{
+    gateway::payload::incoming::{InteractionCreate, MessageCreate},
+    guild::Permissions,
+};
 
-use crate::commands::CommandMeta;
+use crate::commands::args::CommandArgs;
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown};
 use crate::context::Context;
+use crate::database::guild_config::guild_config;
 use crate::util::pagination::{
-    DEFAULT_TIMEOUT_SECS, PaginationInteractionValidation, PaginationModalSubmitValidation,
-    build_paginated_list_view, clamp_page, open_jump_modal_from_token, parse_one_based_page,
-    resolve_modal_target_page, send_paginated_message, total_pages,
-    update_paginated_interaction_message, validate_interaction_for_command,
-    validate_jump_modal_for_command,
+    PaginationInteractionValidation, PaginationModalSubmitValidation, build_paginated_list_view,
+    clamp_page, open_jump_modal_from_token, resolve_modal_target_page, send_paginated_message,
+    timeout_secs, total_pages, update_paginated_interaction_message,
+    validate_interaction_for_command, validate_jump_modal_for_command,
 };
 
 pub const META: CommandMeta = CommandMeta {
@@ -17,8 +22,16 @@ pub const META: CommandMeta = CommandMeta {
     desc: "Test embed pagination behavior.",
     category: "utility",
     usage: "!pagetest [page]",
+    aliases: &[],
+    cooldown: Some(Cooldown::per_user(10)),
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
 };
 
+command!(PagetestCommand => run, paginated);
+
 // TODO: Remove this temporary command after pagination verification is complete.
 
 const ITEMS_PER_PAGE: usize = 5;
@@ -34,16 +47,12 @@ const ITEMS_PER_PAGE: usize = 5;
 /// Error behavior:
 /// - returns usage text on invalid page input.
 /// - returns bounds text when the requested page is out of range.
-pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> anyhow::Result<()> {
+pub async fn run(ctx: Context, msg: Box<MessageCreate>, args: CommandArgs) -> anyhow::Result<()> {
     let http = &ctx.http;
     let items = build_test_items();
     let total = total_pages(items.len(), ITEMS_PER_PAGE);
 
-    let Some(requested_page) = parse_one_based_page(arg1) else {
-        let usage = format!("Usage: `{}` (page starts at 1)", META.usage);
-        http.create_message(msg.channel_id).content(&usage).await?;
-        return Ok(());
-    };
+    let requested_page = args.parser(&ctx, &msg).page()?;
 
     if requested_page > total {
         let out = format!(
@@ -54,14 +63,18 @@ pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> a
         return Ok(());
     }
 
+    let embed_color = guild_config(msg.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
     let (embed, components) = build_paginated_list_view(
         "pagetest",
         "Pagination Test",
+        embed_color,
         &items,
         requested_page,
         ITEMS_PER_PAGE,
         msg.author.id.get(),
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )?;
 
     send_paginated_message(
@@ -70,7 +83,7 @@ pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> a
         embed,
         components,
         total,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
@@ -103,14 +116,18 @@ pub async fn handle_pagination_interaction(
 
     let target_page = clamp_page(token.page, total);
 
+    let embed_color = guild_config(interaction.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
     let (embed, components) = build_paginated_list_view(
         "pagetest",
         "Pagination Test",
+        embed_color,
         &items,
         target_page,
         ITEMS_PER_PAGE,
         actor_id,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )?;
 
     update_paginated_interaction_message(
@@ -119,7 +136,7 @@ pub async fn handle_pagination_interaction(
         embed,
         components,
         total,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
@@ -148,14 +165,18 @@ pub async fn handle_pagination_modal_interaction(
     let total: usize = total_pages(items.len(), ITEMS_PER_PAGE);
     let target_page = resolve_modal_target_page(entered_page, total, total_pages_hint);
 
+    let embed_color = guild_config(interaction.guild_id.map(|id| id.get()))
+        .await
+        .embed_color;
     let (embed, components) = build_paginated_list_view(
         "pagetest",
         "Pagination Test",
+        embed_color,
         &items,
         target_page,
         ITEMS_PER_PAGE,
         actor_id,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )?;
 
     update_paginated_interaction_message(
@@ -164,7 +185,7 @@ pub async fn handle_pagination_modal_interaction(
         embed,
         components,
         total,
-        DEFAULT_TIMEOUT_SECS,
+        timeout_secs(),
     )
     .await?;
 
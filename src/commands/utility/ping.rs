@@ -1,19 +1,69 @@
-use twilight_model::gateway::payload::incoming::MessageCreate;
+use std::time::{Duration, Instant};
 
-use crate::commands::CommandMeta;
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
+
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown};
 use crate::context::Context;
+use crate::shards::state_label;
 
 pub const META: CommandMeta = CommandMeta {
     name: "ping",
-    desc: "Replies with Pong!",
+    desc: "Show gateway and REST latency.",
     category: "utility",
     usage: "!ping",
+    aliases: &[],
+    cooldown: Some(Cooldown::per_channel(5)),
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
 };
 
-/// Send a simple connectivity response.
+command!(PingCommand => |ctx, msg, _args| run(ctx, msg));
+
+/// Reply with heartbeat latency, REST round-trip time and event processing age.
 pub async fn run(ctx: Context, msg: Box<MessageCreate>) -> anyhow::Result<()> {
     let http = &ctx.http;
-    http.create_message(msg.channel_id).content("Pong!").await?;
+    let shard_id = ctx.event.map_or_else(
+        || ctx.shards.shard_for_guild(msg.guild_id),
+        |event| event.shard_id,
+    );
+    let status = ctx.shards.status(shard_id).await;
+
+    let rest_started = Instant::now();
+    let reply = http
+        .create_message(msg.channel_id)
+        .content("Pong!")
+        .await?
+        .model()
+        .await?;
+    let rest_rtt = rest_started.elapsed();
+
+    let heartbeat = status.and_then(|status| status.latency);
+    let since_event = ctx.event.map(|event| event.received_at.elapsed());
+    let state = status.map_or("unknown", |status| state_label(status.state));
+
+    let out = format!(
+        "Pong!\nShard: {}/{} ({})\nHeartbeat: {}\nREST round-trip: {}\nSince event received: {}",
+        shard_id.number(),
+        shard_id.total(),
+        state,
+        format_latency(heartbeat),
+        format_latency(Some(rest_rtt)),
+        format_latency(since_event)
+    );
+
+    http.update_message(msg.channel_id, reply.id)
+        .content(Some(&out))
+        .await?;
 
     Ok(())
 }
+
+fn format_latency(latency: Option<Duration>) -> String {
+    match latency {
+        Some(latency) => format!("{}ms", latency.as_millis()),
+        None => "n/a".to_owned(),
+    }
+}
@@ -1,6 +1,7 @@
-use twilight_model::gateway::payload::incoming::MessageCreate;
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
 
-use crate::commands::CommandMeta;
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown};
 use crate::context::Context;
 
 pub const META: CommandMeta = CommandMeta {
@@ -8,8 +9,16 @@ pub const META: CommandMeta = CommandMeta {
     desc: "The answer to the universe.",
     category: "utility",
     usage: "!universe",
+    aliases: &[],
+    cooldown: Some(Cooldown::per_guild(10)),
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
 };
 
+command!(UniverseCommand => |ctx, msg, _args| run(ctx, msg));
+
 /// Send the universe easter-egg response.
 pub async fn run(ctx: Context, msg: Box<MessageCreate>) -> anyhow::Result<()> {
     let http = &ctx.http;
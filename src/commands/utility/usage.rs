@@ -1,15 +1,26 @@
-use twilight_model::gateway::payload::incoming::MessageCreate;
+use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
 
-use crate::commands::{COMMANDS, CommandMeta};
+use crate::commands::registry::command;
+use crate::commands::{CommandMeta, Cooldown, did_you_mean, find_command};
 use crate::context::Context;
+use crate::database::aliases::guild_aliases;
+use crate::database::guild_config::guild_config;
 
 pub const META: CommandMeta = CommandMeta {
     name: "usage",
     desc: "Show usage syntax for a specific command.",
     category: "utility",
     usage: "!usage <command>",
+    aliases: &[],
+    cooldown: Some(Cooldown::per_user(3)),
+    guild_only: false,
+    owner_only: false,
+    permissions: Permissions::empty(),
+    subcommands: &[],
 };
 
+command!(UsageCommand => |ctx, msg, args| run(ctx, msg, args.arg1()));
+
 /// Show usage for a specific command.
 ///
 /// Purpose:
@@ -20,24 +31,49 @@ pub const META: CommandMeta = CommandMeta {
 ///
 /// Error behavior:
 /// - missing argument returns this command's usage.
-/// - unknown command returns a short not-found message.
+/// - unknown command returns a short not-found message with close matches.
+///
+/// Built-in and custom aliases resolve to their command and are listed, along
+/// with any cooldown.
 pub async fn run(ctx: Context, msg: Box<MessageCreate>, arg1: Option<&str>) -> anyhow::Result<()> {
     let http = &ctx.http;
+    let prefix = guild_config(msg.guild_id.map(|id| id.get())).await.prefix;
     let Some(raw_name) = arg1 else {
-        let usage = format!("Usage: `{}`", META.usage);
+        let usage = META.usage_line(&prefix);
         http.create_message(msg.channel_id).content(&usage).await?;
         return Ok(());
     };
 
-    let lookup = raw_name.trim().trim_start_matches('!').to_ascii_lowercase();
+    let raw_name = raw_name.trim();
+    let lookup = raw_name
+        .strip_prefix(prefix.as_str())
+        .unwrap_or(raw_name)
+        .to_ascii_lowercase();
 
-    let Some(command) = COMMANDS.iter().find(|command| command.name == lookup) else {
-        let out = format!("Unknown command: `{}`", lookup);
+    let aliases = guild_aliases(msg.guild_id.map(|id| id.get())).await;
+    let Some(command) = find_command(&lookup, &aliases) else {
+        let show_owner = ctx.config.current().is_owner(msg.author.id);
+        let mut out = format!("Unknown command: `{}`", lookup);
+        if let Some(suggestion) = did_you_mean(&lookup, &aliases, &prefix, show_owner) {
+            out.push_str(&format!("\n{}", suggestion));
+        }
         http.create_message(msg.channel_id).content(&out).await?;
         return Ok(());
     };
 
-    let out = format!("Usage: `{}`", command.usage);
+    let mut out = command.usage_line(&prefix);
+    let command_aliases = command.all_aliases(&aliases);
+    if !command_aliases.is_empty() {
+        let listed = command_aliases
+            .iter()
+            .map(|alias| format!("`{}{}`", prefix, alias))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!("\nAliases: {}", listed));
+    }
+    if let Some(cooldown) = command.cooldown {
+        out.push_str(&format!("\nCooldown: {}", cooldown.describe()));
+    }
     http.create_message(msg.channel_id).content(&out).await?;
 
     Ok(())
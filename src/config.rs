@@ -0,0 +1,422 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use anyhow::{Context as _, bail};
+use tracing::Level;
+use tracing_subscriber::{Registry, filter::LevelFilter, reload};
+use twilight_gateway::Intents;
+use twilight_model::id::{Id, marker::UserMarker};
+
+use crate::database::guild_config::{self, ConfigKey, GuildConfig};
+use crate::util::pagination::{self, DEFAULT_PAGINATION_TIMEOUT_SECS};
+
+/// Config file read when `RUSTY_CONFIG` is not set. It may be absent.
+const DEFAULT_CONFIG_PATH: &str = "rusty.toml";
+
+/// Directory for files the bot saves, used when `data_dir` is not set.
+const DEFAULT_DATA_DIR: &str = "data";
+
+/// Longest accepted pagination session, in seconds.
+const MAX_PAGINATION_TIMEOUT_SECS: u64 = 60 * 60;
+
+/// Config file keys besides the per-feature `[defaults]`, see [`default_setting_names`].
+const FILE_KEYS: &[&str] = &[
+    "token",
+    "owners",
+    "log_level",
+    "data_dir",
+    "gateway.intents",
+    "gateway.shards",
+    "pagination.timeout_secs",
+];
+
+/// Validated bot-wide settings.
+#[derive(Clone, Debug)]
+pub struct BotConfig {
+    /// File the settings were read from, if it existed.
+    pub path: Option<PathBuf>,
+    pub token: String,
+    pub owners: Vec<Id<UserMarker>>,
+    /// Level used unless `RUST_LOG` is set.
+    pub log_level: Level,
+    /// Directory the bot saves its files in, such as the blacklist.
+    pub data_dir: PathBuf,
+    pub intents: Intents,
+    /// Fixed shard count, or `None` to use Discord's recommendation.
+    pub shard_count: Option<u32>,
+    pub pagination_timeout_secs: u64,
+    /// Settings every guild starts out with before `!config` changes them.
+    pub guild_defaults: GuildConfig,
+}
+
+impl BotConfig {
+    /// Whether a user is one of the configured bot owners.
+    pub fn is_owner(&self, user_id: Id<UserMarker>) -> bool {
+        self.owners.contains(&user_id)
+    }
+
+    /// File the global blacklist is kept in.
+    pub fn blacklist_path(&self) -> PathBuf {
+        self.data_dir.join("blacklist.txt")
+    }
+
+    /// Load the config file named by `RUSTY_CONFIG` (default `rusty.toml`) and
+    /// apply environment overrides.
+    ///
+    /// A missing default file is fine; every other problem is reported with the
+    /// offending key or variable.
+    pub fn load() -> anyhow::Result<Self> {
+        let (path, required) = match env_value("RUSTY_CONFIG") {
+            Some(path) => (PathBuf::from(path), true),
+            None => (PathBuf::from(DEFAULT_CONFIG_PATH), false),
+        };
+
+        let file = match fs::read_to_string(&path) {
+            Ok(contents) => Some(parse_file(&path, &contents)?),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound && !required => None,
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("failed to read config file {}", path.display()));
+            }
+        };
+
+        let sources = Sources {
+            path: file.as_ref().map(|_| path),
+            file: file.unwrap_or_default(),
+        };
+        sources.resolve()
+    }
+}
+
+/// Handle for changing the log level of the installed subscriber.
+pub type LogLevelHandle = reload::Handle<LevelFilter, Registry>;
+
+/// Shared bot config that can be swapped at runtime.
+///
+/// Cheap to clone because it only stores reference-counted shared state.
+#[derive(Clone)]
+pub struct ConfigHandle {
+    current: Arc<RwLock<Arc<BotConfig>>>,
+    /// `None` when `RUST_LOG` decides the log level instead of the config.
+    log_level: Option<LogLevelHandle>,
+}
+
+impl ConfigHandle {
+    /// Wrap a loaded config and apply its runtime settings.
+    pub fn new(config: BotConfig, log_level: Option<LogLevelHandle>) -> Self {
+        let handle = Self {
+            current: Arc::new(RwLock::new(Arc::new(config))),
+            log_level,
+        };
+        handle.apply(&handle.current());
+        handle
+    }
+
+    /// The config currently in effect.
+    pub fn current(&self) -> Arc<BotConfig> {
+        Arc::clone(&self.current.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Load the config again and swap it in if it is valid.
+    ///
+    /// Returns one line per changed setting. Settings that only take effect
+    /// when connecting keep their running values and are reported as such.
+    pub fn reload(&self) -> anyhow::Result<Vec<String>> {
+        let old = self.current();
+        let mut new = BotConfig::load()?;
+
+        let mut changes = Vec::new();
+        if new.token != old.token {
+            changes.push("token changed (takes effect after restart)".to_owned());
+        }
+        if new.intents != old.intents {
+            changes.push(format!(
+                "gateway.intents: {:?} → {:?} (takes effect after restart)",
+                old.intents, new.intents
+            ));
+        }
+        if new.shard_count != old.shard_count {
+            changes.push(format!(
+                "gateway.shards: {} → {} (takes effect after restart)",
+                shard_label(old.shard_count),
+                shard_label(new.shard_count)
+            ));
+        }
+        if new.data_dir != old.data_dir {
+            changes.push(format!(
+                "data_dir: {} → {} (takes effect after restart)",
+                old.data_dir.display(),
+                new.data_dir.display()
+            ));
+        }
+        new.token.clone_from(&old.token);
+        new.intents = old.intents;
+        new.shard_count = old.shard_count;
+        new.data_dir.clone_from(&old.data_dir);
+
+        if new.owners != old.owners {
+            changes.push(format!(
+                "owners: {} → {} user(s)",
+                old.owners.len(),
+                new.owners.len()
+            ));
+        }
+        if new.log_level != old.log_level {
+            let note = if self.log_level.is_none() {
+                " (RUST_LOG takes precedence)"
+            } else {
+                ""
+            };
+            changes.push(format!(
+                "log_level: {} → {}{}",
+                old.log_level, new.log_level, note
+            ));
+        }
+        if new.pagination_timeout_secs != old.pagination_timeout_secs {
+            changes.push(format!(
+                "pagination.timeout_secs: {} → {}",
+                old.pagination_timeout_secs, new.pagination_timeout_secs
+            ));
+        }
+        for key in ConfigKey::ALL {
+            let before = old.guild_defaults.display(key);
+            let after = new.guild_defaults.display(key);
+            if before != after {
+                changes.push(format!("defaults.{}: {} → {}", key.name(), before, after));
+            }
+        }
+
+        let new = Arc::new(new);
+        self.apply(&new);
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = new;
+
+        Ok(changes)
+    }
+
+    /// Push settings read elsewhere through globals into effect.
+    fn apply(&self, config: &BotConfig) {
+        pagination::set_timeout_secs(config.pagination_timeout_secs);
+        guild_config::set_defaults(config.guild_defaults.clone());
+
+        if let Some(log_level) = &self.log_level
+            && let Err(source) = log_level.reload(LevelFilter::from_level(config.log_level))
+        {
+            tracing::error!(?source, "failed to change log level");
+        }
+    }
+}
+
+fn shard_label(shard_count: Option<u32>) -> String {
+    shard_count.map_or_else(|| "auto".to_owned(), |total| total.to_string())
+}
+
+/// File values flattened to dotted keys, plus where they came from.
+struct Sources {
+    path: Option<PathBuf>,
+    file: BTreeMap<String, String>,
+}
+
+impl Sources {
+    fn resolve(self) -> anyhow::Result<BotConfig> {
+        let Some(token) = self.setting("token", "DISCORD_TOKEN", "a bot token", |raw| {
+            Some(raw.to_owned())
+        })?
+        else {
+            bail!("missing bot token: set DISCORD_TOKEN or `token` in the config file");
+        };
+
+        let owners = self
+            .setting("owners", "RUSTY_OWNERS", "a list of user IDs", parse_owners)?
+            .unwrap_or_default();
+
+        let log_level = self
+            .setting(
+                "log_level",
+                "RUSTY_LOG_LEVEL",
+                "one of trace, debug, info, warn, error",
+                |raw| Level::from_str(raw).ok(),
+            )?
+            .unwrap_or(Level::INFO);
+
+        let data_dir = self
+            .setting("data_dir", "RUSTY_DATA_DIR", "a directory path", |raw| {
+                Some(PathBuf::from(raw))
+            })?
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR));
+
+        let intents = self
+            .setting(
+                "gateway.intents",
+                "RUSTY_INTENTS",
+                "a list of gateway intent names like GUILD_MESSAGES",
+                parse_intents,
+            )?
+            .unwrap_or(Intents::GUILDS | Intents::GUILD_MESSAGES | Intents::MESSAGE_CONTENT);
+
+        let shard_count = self.setting(
+            "gateway.shards",
+            "RUSTY_SHARD_COUNT",
+            "a positive integer",
+            |raw| raw.parse::<u32>().ok().filter(|total| *total >= 1),
+        )?;
+
+        let pagination_timeout_secs = self
+            .setting(
+                "pagination.timeout_secs",
+                "RUSTY_PAGINATION_TIMEOUT_SECS",
+                &format!("a number of seconds between 1 and {MAX_PAGINATION_TIMEOUT_SECS}"),
+                |raw| {
+                    raw.parse::<u64>()
+                        .ok()
+                        .filter(|secs| (1..=MAX_PAGINATION_TIMEOUT_SECS).contains(secs))
+                },
+            )?
+            .unwrap_or(DEFAULT_PAGINATION_TIMEOUT_SECS);
+
+        let mut guild_defaults = GuildConfig::default();
+        for key in ConfigKey::ALL {
+            let (file_key, env_var) = default_setting_names(key);
+            if let Some(value) = self.setting(&file_key, &env_var, &key.expected(), |raw| {
+                key.parse_value(raw)
+            })? {
+                guild_defaults.set(value);
+            }
+        }
+
+        Ok(BotConfig {
+            path: self.path,
+            token,
+            owners,
+            log_level,
+            data_dir,
+            intents,
+            shard_count,
+            pagination_timeout_secs,
+            guild_defaults,
+        })
+    }
+
+    /// Look up one setting, preferring the environment over the file.
+    fn setting<T>(
+        &self,
+        key: &str,
+        env_var: &str,
+        expected: &str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> anyhow::Result<Option<T>> {
+        let (raw, origin) = match env_value(env_var) {
+            Some(raw) => (raw, format!("environment variable {env_var}")),
+            None => match self.file.get(key) {
+                Some(raw) => (raw.clone(), format!("`{key}` in {}", self.file_label())),
+                None => return Ok(None),
+            },
+        };
+
+        match parse(raw.trim()) {
+            Some(value) => Ok(Some(value)),
+            None => bail!("invalid {origin}: expected {expected}, got `{raw}`"),
+        }
+    }
+
+    fn file_label(&self) -> String {
+        self.path.as_deref().map_or_else(
+            || DEFAULT_CONFIG_PATH.to_owned(),
+            |path| path.display().to_string(),
+        )
+    }
+}
+
+/// Config file key and environment variable of a per-feature default.
+fn default_setting_names(key: ConfigKey) -> (String, String) {
+    (
+        format!("defaults.{}", key.name()),
+        format!("RUSTY_DEFAULT_{}", key.name().to_ascii_uppercase()),
+    )
+}
+
+/// Parse a config file into dotted keys with string values.
+///
+/// Values are kept as text so file and environment values share one parser.
+fn parse_file(path: &Path, contents: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let table: toml::Table = contents
+        .parse()
+        .with_context(|| format!("failed to parse config file {}", path.display()))?;
+
+    let mut known: Vec<String> = FILE_KEYS.iter().map(|key| (*key).to_owned()).collect();
+    known.extend(ConfigKey::ALL.map(|key| default_setting_names(key).0));
+
+    let mut flat = BTreeMap::new();
+    flatten_table(&table, "", &mut flat).with_context(|| format!("in {}", path.display()))?;
+
+    if let Some(unknown) = flat.keys().find(|key| !known.contains(key)) {
+        bail!("unknown setting `{unknown}` in {}", path.display());
+    }
+
+    Ok(flat)
+}
+
+fn flatten_table(
+    table: &toml::Table,
+    prefix: &str,
+    out: &mut BTreeMap<String, String>,
+) -> anyhow::Result<()> {
+    for (name, value) in table {
+        let key = format!("{prefix}{name}");
+        match value {
+            toml::Value::Table(inner) => flatten_table(inner, &format!("{key}."), out)?,
+            toml::Value::Array(items) => {
+                let items = items
+                    .iter()
+                    .map(scalar_text)
+                    .collect::<Option<Vec<_>>>()
+                    .with_context(|| format!("`{key}` must be a list of plain values"))?;
+                out.insert(key, items.join(","));
+            }
+            scalar => {
+                let text = scalar_text(scalar)
+                    .with_context(|| format!("`{key}` must be a plain value"))?;
+                out.insert(key, text);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn scalar_text(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(text) => Some(text.clone()),
+        toml::Value::Integer(number) => Some(number.to_string()),
+        toml::Value::Float(number) => Some(number.to_string()),
+        toml::Value::Boolean(flag) => Some(flag.to_string()),
+        toml::Value::Datetime(datetime) => Some(datetime.to_string()),
+        toml::Value::Array(_) | toml::Value::Table(_) => None,
+    }
+}
+
+fn parse_owners(raw: &str) -> Option<Vec<Id<UserMarker>>> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse::<u64>().ok().and_then(Id::new_checked))
+        .collect()
+}
+
+fn parse_intents(raw: &str) -> Option<Intents> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .try_fold(Intents::empty(), |intents, name| {
+            Some(intents | Intents::from_name(&name.to_ascii_uppercase())?)
+        })
+}
+
+/// An environment variable's value, treating empty values as unset.
+fn env_value(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
@@ -1,6 +1,23 @@
 use std::sync::Arc;
+use std::time::Instant;
 
+use tokio::sync::Notify;
+use twilight_gateway::{Intents, ShardId};
 use twilight_http::Client;
+use twilight_model::id::{Id, marker::UserMarker};
+
+use crate::cache::Cache;
+use crate::config::ConfigHandle;
+use crate::shards::ShardRegistry;
+
+/// Gateway metadata for the event currently being handled.
+#[derive(Clone, Copy, Debug)]
+pub struct EventInfo {
+    /// Shard that delivered the event.
+    pub shard_id: ShardId,
+    /// When the shard task received the event.
+    pub received_at: Instant,
+}
 
 /// Shared application context passed into command handlers.
 ///
@@ -8,11 +25,47 @@ use twilight_http::Client;
 #[derive(Clone)]
 pub struct Context {
     pub http: Arc<Client>,
+    pub cache: Cache,
+    pub shards: ShardRegistry,
+    /// The bot's own user, used to recognize mentions as a command prefix.
+    pub bot_user_id: Id<UserMarker>,
+    /// Bot-wide settings, swapped in place on reload.
+    pub config: ConfigHandle,
+    /// When the bot process started.
+    pub started_at: Instant,
+    /// Notified to request a clean shutdown from inside a handler.
+    pub shutdown: Arc<Notify>,
+    /// Set by the dispatcher for the event a handler was spawned for.
+    pub event: Option<EventInfo>,
 }
 
 impl Context {
     /// Create a new application context.
-    pub fn new(http: Arc<Client>) -> Self {
-        Self { http }
+    pub fn new(
+        http: Arc<Client>,
+        shards: ShardRegistry,
+        bot_user_id: Id<UserMarker>,
+        config: ConfigHandle,
+    ) -> Self {
+        let member_events = config.current().intents.contains(Intents::GUILD_MEMBERS);
+
+        Self {
+            http,
+            cache: Cache::new(member_events),
+            shards,
+            bot_user_id,
+            config,
+            started_at: Instant::now(),
+            shutdown: Arc::new(Notify::new()),
+            event: None,
+        }
+    }
+
+    /// Clone this context for handling a specific gateway event.
+    pub fn with_event(&self, event: EventInfo) -> Self {
+        Self {
+            event: Some(event),
+            ..self.clone()
+        }
     }
 }
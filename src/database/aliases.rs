@@ -0,0 +1,57 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::OnceLock,
+};
+
+use tokio::sync::RwLock;
+
+/// Most custom aliases a single guild may define.
+pub const MAX_ALIASES_PER_GUILD: usize = 25;
+
+static GUILD_ALIASES: OnceLock<RwLock<HashMap<u64, BTreeMap<String, String>>>> = OnceLock::new();
+
+fn alias_store() -> &'static RwLock<HashMap<u64, BTreeMap<String, String>>> {
+    GUILD_ALIASES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Custom aliases of a guild, mapping alias to command name.
+pub async fn guild_aliases(guild_id: Option<u64>) -> BTreeMap<String, String> {
+    let Some(guild_id) = guild_id else {
+        return BTreeMap::new();
+    };
+
+    alias_store()
+        .read()
+        .await
+        .get(&guild_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Point an alias at a command, replacing any previous target.
+///
+/// Returns `false` without changes when the guild is at its alias limit.
+pub async fn set_alias(guild_id: u64, alias: &str, command: &str) -> bool {
+    let mut store = alias_store().write().await;
+    let aliases = store.entry(guild_id).or_default();
+
+    if !aliases.contains_key(alias) && aliases.len() >= MAX_ALIASES_PER_GUILD {
+        return false;
+    }
+
+    aliases.insert(alias.to_owned(), command.to_owned());
+    true
+}
+
+/// Remove an alias and return the command it pointed at.
+pub async fn remove_alias(guild_id: u64, alias: &str) -> Option<String> {
+    let mut store = alias_store().write().await;
+    let aliases = store.get_mut(&guild_id)?;
+    let command = aliases.remove(alias);
+
+    if aliases.is_empty() {
+        store.remove(&guild_id);
+    }
+
+    command
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::{Context as _, bail};
+use tokio::sync::RwLock;
+
+/// What a blacklist entry refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlacklistKind {
+    User,
+    Guild,
+}
+
+impl BlacklistKind {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.to_ascii_lowercase().as_str() {
+            "user" | "users" => Some(Self::User),
+            "guild" | "guilds" | "server" => Some(Self::Guild),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Guild => "guild",
+        }
+    }
+}
+
+/// Users and guilds the bot refuses to serve.
+#[derive(Default)]
+struct Blacklist {
+    /// File every change is written to; unset until [`load`] runs.
+    path: Option<PathBuf>,
+    users: BTreeSet<u64>,
+    guilds: BTreeSet<u64>,
+}
+
+impl Blacklist {
+    fn ids(&self, kind: BlacklistKind) -> &BTreeSet<u64> {
+        match kind {
+            BlacklistKind::User => &self.users,
+            BlacklistKind::Guild => &self.guilds,
+        }
+    }
+
+    fn ids_mut(&mut self, kind: BlacklistKind) -> &mut BTreeSet<u64> {
+        match kind {
+            BlacklistKind::User => &mut self.users,
+            BlacklistKind::Guild => &mut self.guilds,
+        }
+    }
+
+    /// One `user <id>` or `guild <id>` line per entry.
+    fn render(&self) -> String {
+        let mut out = String::from("# Managed with !blacklist; edits are read on startup.\n");
+        for kind in [BlacklistKind::User, BlacklistKind::Guild] {
+            for id in self.ids(kind) {
+                out.push_str(&format!("{} {}\n", kind.label(), id));
+            }
+        }
+        out
+    }
+
+    async fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Write beside the file and rename so a crash never leaves it half-written.
+        let temp = path.with_extension("tmp");
+        tokio::fs::write(&temp, self.render()).await?;
+        tokio::fs::rename(&temp, path).await?;
+
+        Ok(())
+    }
+}
+
+fn parse_file(path: &Path, contents: &str) -> anyhow::Result<Blacklist> {
+    let mut blacklist = Blacklist::default();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let entry = line.split_once(char::is_whitespace).and_then(|(kind, id)| {
+            let kind = BlacklistKind::parse(kind)?;
+            let id = id.trim().parse::<u64>().ok().filter(|id| *id != 0)?;
+            Some((kind, id))
+        });
+        let Some((kind, id)) = entry else {
+            bail!(
+                "{}:{}: expected `user <id>` or `guild <id>`, got `{}`",
+                path.display(),
+                index + 1,
+                line
+            );
+        };
+
+        blacklist.ids_mut(kind).insert(id);
+    }
+
+    Ok(blacklist)
+}
+
+static BLACKLIST: OnceLock<RwLock<Blacklist>> = OnceLock::new();
+
+fn blacklist_store() -> &'static RwLock<Blacklist> {
+    BLACKLIST.get_or_init(|| RwLock::new(Blacklist::default()))
+}
+
+/// Read the blacklist from `path` and save every later change back to it.
+///
+/// A missing file is an empty blacklist.
+pub async fn load(path: &Path) -> anyhow::Result<()> {
+    let mut loaded = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => parse_file(path, &contents)?,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Blacklist::default(),
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("failed to read blacklist {}", path.display()));
+        }
+    };
+    loaded.path = Some(path.to_owned());
+
+    *blacklist_store().write().await = loaded;
+
+    Ok(())
+}
+
+/// Whether an ID is blacklisted.
+pub async fn is_blacklisted(kind: BlacklistKind, id: u64) -> bool {
+    blacklist_store().read().await.ids(kind).contains(&id)
+}
+
+/// Every blacklisted ID of one kind, in ascending order.
+pub async fn blacklisted(kind: BlacklistKind) -> Vec<u64> {
+    blacklist_store()
+        .read()
+        .await
+        .ids(kind)
+        .iter()
+        .copied()
+        .collect()
+}
+
+/// Blacklist an ID and save the list. Returns `false` if it was already listed.
+///
+/// If saving fails the ID is taken off again, so memory matches the file.
+pub async fn add_to_blacklist(kind: BlacklistKind, id: u64) -> anyhow::Result<bool> {
+    let mut store = blacklist_store().write().await;
+    if !store.ids_mut(kind).insert(id) {
+        return Ok(false);
+    }
+
+    if let Err(source) = store.save().await {
+        store.ids_mut(kind).remove(&id);
+        return Err(source.context("failed to save blacklist"));
+    }
+    Ok(true)
+}
+
+/// Lift a blacklist entry and save the list. Returns `false` if the ID wasn't listed.
+///
+/// If saving fails the ID is listed again, so memory matches the file.
+pub async fn remove_from_blacklist(kind: BlacklistKind, id: u64) -> anyhow::Result<bool> {
+    let mut store = blacklist_store().write().await;
+    if !store.ids_mut(kind).remove(&id) {
+        return Ok(false);
+    }
+
+    if let Err(source) = store.save().await {
+        store.ids_mut(kind).insert(id);
+        return Err(source.context("failed to save blacklist"));
+    }
+    Ok(true)
+}
@@ -0,0 +1,84 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::OnceLock,
+};
+
+use tokio::sync::RwLock;
+
+/// How granted roles combine with a command's default Discord permission.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccessMode {
+    /// Granted roles may run the command in addition to members with the permission.
+    #[default]
+    Extend,
+    /// Only granted roles (and administrators) may run the command.
+    Replace,
+}
+
+impl AccessMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            AccessMode::Extend => "extend",
+            AccessMode::Replace => "replace",
+        }
+    }
+}
+
+/// Roles granted access to one command in one guild.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandAccess {
+    pub roles: BTreeSet<u64>,
+    pub mode: AccessMode,
+}
+
+static COMMAND_ACCESS: OnceLock<RwLock<HashMap<u64, BTreeMap<String, CommandAccess>>>> =
+    OnceLock::new();
+
+fn access_store() -> &'static RwLock<HashMap<u64, BTreeMap<String, CommandAccess>>> {
+    COMMAND_ACCESS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Access overrides of one command in a guild, or the empty default.
+pub async fn command_access(guild_id: u64, command: &str) -> CommandAccess {
+    access_store()
+        .read()
+        .await
+        .get(&guild_id)
+        .and_then(|overrides| overrides.get(command))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// All non-default access overrides of a guild, keyed by command name.
+pub async fn guild_access(guild_id: u64) -> BTreeMap<String, CommandAccess> {
+    access_store()
+        .read()
+        .await
+        .get(&guild_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Edit a command's access overrides in place and return the result.
+///
+/// Overrides edited back to the default are dropped from the store.
+pub async fn update_access(
+    guild_id: u64,
+    command: &str,
+    edit: impl FnOnce(&mut CommandAccess),
+) -> CommandAccess {
+    let mut store = access_store().write().await;
+    let overrides = store.entry(guild_id).or_default();
+    let access = overrides.entry(command.to_owned()).or_default();
+    edit(access);
+    let updated = access.clone();
+
+    if updated == CommandAccess::default() {
+        overrides.remove(command);
+    }
+    if overrides.is_empty() {
+        store.remove(&guild_id);
+    }
+
+    updated
+}
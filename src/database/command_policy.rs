@@ -0,0 +1,119 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::OnceLock,
+};
+
+use tokio::sync::RwLock;
+
+/// Where and by whom a command may be used in one guild.
+///
+/// Empty allow lists mean "everywhere" / "everyone"; deny lists always win.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandPolicy {
+    pub disabled: bool,
+    pub allowed_channels: BTreeSet<u64>,
+    pub denied_channels: BTreeSet<u64>,
+    pub allowed_roles: BTreeSet<u64>,
+    pub denied_roles: BTreeSet<u64>,
+}
+
+/// Why a policy rejected a command invocation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyRefusal {
+    Disabled,
+    ChannelDenied,
+    ChannelNotAllowed(Vec<u64>),
+    RoleDenied,
+    RoleNotAllowed,
+}
+
+impl CommandPolicy {
+    /// Check an invocation in `channel_ids` (a channel and, for threads, its parent)
+    /// by a member holding `role_ids`.
+    pub fn check(&self, channel_ids: &[u64], role_ids: &[u64]) -> Option<PolicyRefusal> {
+        if self.disabled {
+            return Some(PolicyRefusal::Disabled);
+        }
+
+        if channel_ids
+            .iter()
+            .any(|id| self.denied_channels.contains(id))
+        {
+            return Some(PolicyRefusal::ChannelDenied);
+        }
+
+        if !self.allowed_channels.is_empty()
+            && !channel_ids
+                .iter()
+                .any(|id| self.allowed_channels.contains(id))
+        {
+            return Some(PolicyRefusal::ChannelNotAllowed(
+                self.allowed_channels.iter().copied().collect(),
+            ));
+        }
+
+        if role_ids.iter().any(|id| self.denied_roles.contains(id)) {
+            return Some(PolicyRefusal::RoleDenied);
+        }
+
+        if !self.allowed_roles.is_empty()
+            && !role_ids.iter().any(|id| self.allowed_roles.contains(id))
+        {
+            return Some(PolicyRefusal::RoleNotAllowed);
+        }
+
+        None
+    }
+}
+
+static COMMAND_POLICIES: OnceLock<RwLock<HashMap<u64, BTreeMap<String, CommandPolicy>>>> =
+    OnceLock::new();
+
+fn policy_store() -> &'static RwLock<HashMap<u64, BTreeMap<String, CommandPolicy>>> {
+    COMMAND_POLICIES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Policy of one command in a guild, or the permissive default.
+pub async fn command_policy(guild_id: u64, command: &str) -> CommandPolicy {
+    policy_store()
+        .read()
+        .await
+        .get(&guild_id)
+        .and_then(|policies| policies.get(command))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// All non-default command policies of a guild, keyed by command name.
+pub async fn guild_policies(guild_id: u64) -> BTreeMap<String, CommandPolicy> {
+    policy_store()
+        .read()
+        .await
+        .get(&guild_id)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Edit a command's policy in place and return the result.
+///
+/// Policies edited back to the default are dropped from the store.
+pub async fn update_policy(
+    guild_id: u64,
+    command: &str,
+    edit: impl FnOnce(&mut CommandPolicy),
+) -> CommandPolicy {
+    let mut store = policy_store().write().await;
+    let policies = store.entry(guild_id).or_default();
+    let policy = policies.entry(command.to_owned()).or_default();
+    edit(policy);
+    let updated = policy.clone();
+
+    if updated == CommandPolicy::default() {
+        policies.remove(command);
+    }
+    if policies.is_empty() {
+        store.remove(&guild_id);
+    }
+
+    updated
+}
@@ -0,0 +1,54 @@
+use std::{collections::HashMap, sync::OnceLock, time::Duration};
+
+use tokio::sync::RwLock;
+
+/// Usage counters of one command since startup.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CommandStats {
+    pub runs: u64,
+    pub failures: u64,
+    pub total_time: Duration,
+}
+
+impl CommandStats {
+    pub fn average_time(&self) -> Duration {
+        match u32::try_from(self.runs) {
+            Ok(0) => Duration::ZERO,
+            Ok(runs) => self.total_time / runs,
+            Err(_) => Duration::ZERO,
+        }
+    }
+}
+
+static COMMAND_STATS: OnceLock<RwLock<HashMap<String, CommandStats>>> = OnceLock::new();
+
+fn stats_store() -> &'static RwLock<HashMap<String, CommandStats>> {
+    COMMAND_STATS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Count one run of a command, keyed by its qualified name.
+pub async fn record_run(command: &str, elapsed: Duration, failed: bool) {
+    let mut store = stats_store().write().await;
+    let stats = store.entry(command.to_owned()).or_default();
+    stats.runs += 1;
+    stats.failures += u64::from(failed);
+    stats.total_time += elapsed;
+}
+
+/// Every command run since startup, busiest first.
+pub async fn command_stats() -> Vec<(String, CommandStats)> {
+    let store = stats_store().read().await;
+    let mut stats: Vec<(String, CommandStats)> = store
+        .iter()
+        .map(|(name, stats)| (name.clone(), *stats))
+        .collect();
+
+    stats.sort_unstable_by(|left, right| {
+        right
+            .1
+            .runs
+            .cmp(&left.1.runs)
+            .then_with(|| left.0.cmp(&right.0))
+    });
+    stats
+}
@@ -0,0 +1,71 @@
+use std::{
+    collections::HashMap,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::RwLock;
+
+/// A running cooldown and whether the user was already told about it.
+struct CooldownEntry {
+    ends_at: Instant,
+    notified: bool,
+}
+
+/// Result of trying to use a command under cooldown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CooldownState {
+    Ready,
+    /// Still cooling down; `first_refusal` is set only for the first refused use.
+    Waiting {
+        remaining: Duration,
+        first_refusal: bool,
+    },
+}
+
+/// Running cooldowns keyed by qualified command name and bucket ID (user, channel or guild).
+static COOLDOWNS: OnceLock<RwLock<HashMap<(String, u64), CooldownEntry>>> = OnceLock::new();
+
+fn cooldown_store() -> &'static RwLock<HashMap<(String, u64), CooldownEntry>> {
+    COOLDOWNS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Cancel a running cooldown, e.g. when the use that started it was a typo.
+pub async fn release_cooldown(command: &str, bucket_id: u64) {
+    cooldown_store()
+        .write()
+        .await
+        .remove(&(command.to_owned(), bucket_id));
+}
+
+/// Start a cooldown for a command in a bucket, unless one is already running.
+///
+/// Expired cooldowns are dropped on every call so the store never grows past
+/// the cooldowns currently running.
+pub async fn hit_cooldown(command: &str, bucket_id: u64, length: Duration) -> CooldownState {
+    let now = Instant::now();
+    let mut store = cooldown_store().write().await;
+    store.retain(|_, entry| entry.ends_at > now);
+
+    let key = (command.to_owned(), bucket_id);
+    match store.get_mut(&key) {
+        Some(entry) => {
+            let first_refusal = !entry.notified;
+            entry.notified = true;
+            CooldownState::Waiting {
+                remaining: entry.ends_at - now,
+                first_refusal,
+            }
+        }
+        None => {
+            store.insert(
+                key,
+                CooldownEntry {
+                    ends_at: now + length,
+                    notified: false,
+                },
+            );
+            CooldownState::Ready
+        }
+    }
+}
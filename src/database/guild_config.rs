@@ -0,0 +1,331 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, PoisonError, RwLock as StdRwLock},
+};
+
+use tokio::sync::RwLock;
+
+use crate::util::DEFAULT_COMMAND_PREFIX;
+use crate::util::embed::DEFAULT_EMBED_COLOR;
+use crate::util::parse::{format_duration_seconds, parse_duration_seconds};
+
+/// Longest accepted command prefix, in characters.
+const MAX_PREFIX_CHARS: usize = 5;
+
+/// Timeout length used when `!timeout` is given no duration.
+const DEFAULT_TIMEOUT_SECS: u64 = 10 * 60;
+
+/// Longest timeout Discord accepts (28 days).
+pub const MAX_TIMEOUT_SECS: u64 = 28 * 24 * 60 * 60;
+
+/// Window used when `!warnings` is given no day count.
+const DEFAULT_WARNING_DAYS: u64 = 30;
+
+pub const MAX_WARNING_DAYS: u64 = 3650;
+
+/// Discord's bulk delete limit, which also caps `!purge`.
+pub const BULK_DELETE_LIMIT: u16 = 100;
+
+/// Effective settings of a guild: its own values over the bot-wide defaults.
+///
+/// `Default` gives the built-in values the bot config file starts from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuildConfig {
+    pub prefix: String,
+    pub timeout_secs: u64,
+    pub warning_days: u64,
+    pub purge_max: u16,
+    pub embed_color: u32,
+    pub command_suggestions: bool,
+}
+
+impl Default for GuildConfig {
+    fn default() -> Self {
+        Self {
+            prefix: DEFAULT_COMMAND_PREFIX.to_owned(),
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            warning_days: DEFAULT_WARNING_DAYS,
+            purge_max: BULK_DELETE_LIMIT,
+            embed_color: DEFAULT_EMBED_COLOR,
+            command_suggestions: false,
+        }
+    }
+}
+
+/// Settable configuration keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigKey {
+    Prefix,
+    TimeoutDuration,
+    WarningDays,
+    PurgeMax,
+    EmbedColor,
+    CommandSuggestions,
+}
+
+/// A validated value for one configuration key.
+#[derive(Clone, Debug)]
+pub enum ConfigValue {
+    Prefix(String),
+    TimeoutDuration(u64),
+    WarningDays(u64),
+    PurgeMax(u16),
+    EmbedColor(u32),
+    CommandSuggestions(bool),
+}
+
+impl ConfigKey {
+    pub const ALL: [ConfigKey; 6] = [
+        ConfigKey::Prefix,
+        ConfigKey::TimeoutDuration,
+        ConfigKey::WarningDays,
+        ConfigKey::PurgeMax,
+        ConfigKey::EmbedColor,
+        ConfigKey::CommandSuggestions,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ConfigKey::Prefix => "prefix",
+            ConfigKey::TimeoutDuration => "timeout_duration",
+            ConfigKey::WarningDays => "warning_days",
+            ConfigKey::PurgeMax => "purge_max",
+            ConfigKey::EmbedColor => "embed_color",
+            ConfigKey::CommandSuggestions => "command_suggestions",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ConfigKey::Prefix => {
+                "Prefix for message commands. Mentioning the bot always works too."
+            }
+            ConfigKey::TimeoutDuration => "Default `timeout` duration.",
+            ConfigKey::WarningDays => "Default `warnings` window in days.",
+            ConfigKey::PurgeMax => "Most messages a single `purge` may delete.",
+            ConfigKey::EmbedColor => "Color of moderation embeds.",
+            ConfigKey::CommandSuggestions => {
+                "Suggest similar commands when an unknown one is used."
+            }
+        }
+    }
+
+    /// Human-readable description of the accepted values.
+    pub fn expected(self) -> String {
+        match self {
+            ConfigKey::Prefix => {
+                format!("1 to {MAX_PREFIX_CHARS} characters without spaces or backticks")
+            }
+            ConfigKey::TimeoutDuration => format!(
+                "a duration between 1s and {} (e.g. `10m`, `2h`)",
+                format_duration_seconds(MAX_TIMEOUT_SECS)
+            ),
+            ConfigKey::WarningDays => format!("a number of days between 1 and {MAX_WARNING_DAYS}"),
+            ConfigKey::PurgeMax => format!("a number between 1 and {BULK_DELETE_LIMIT}"),
+            ConfigKey::EmbedColor => "a hex color like `#905430`".to_owned(),
+            ConfigKey::CommandSuggestions => "`on` or `off`".to_owned(),
+        }
+    }
+
+    /// Look up a key by name (case-insensitive).
+    pub fn parse(raw: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|key| key.name().eq_ignore_ascii_case(raw.trim()))
+    }
+
+    /// Validate a raw value for this key.
+    pub fn parse_value(self, raw: &str) -> Option<ConfigValue> {
+        let raw = raw.trim();
+        match self {
+            ConfigKey::Prefix => Some(raw)
+                .filter(|prefix| (1..=MAX_PREFIX_CHARS).contains(&prefix.chars().count()))
+                .filter(|prefix| !prefix.contains(|ch: char| ch.is_whitespace() || ch == '`'))
+                .map(|prefix| ConfigValue::Prefix(prefix.to_owned())),
+            ConfigKey::TimeoutDuration => parse_duration_seconds(raw)
+                .filter(|secs| *secs <= MAX_TIMEOUT_SECS)
+                .map(ConfigValue::TimeoutDuration),
+            ConfigKey::WarningDays => raw
+                .parse::<u64>()
+                .ok()
+                .filter(|days| (1..=MAX_WARNING_DAYS).contains(days))
+                .map(ConfigValue::WarningDays),
+            ConfigKey::PurgeMax => raw
+                .parse::<u16>()
+                .ok()
+                .filter(|amount| (1..=BULK_DELETE_LIMIT).contains(amount))
+                .map(ConfigValue::PurgeMax),
+            ConfigKey::EmbedColor => parse_hex_color(raw).map(ConfigValue::EmbedColor),
+            ConfigKey::CommandSuggestions => parse_switch(raw).map(ConfigValue::CommandSuggestions),
+        }
+    }
+}
+
+impl ConfigValue {
+    /// The key this value is for.
+    pub fn key(&self) -> ConfigKey {
+        match self {
+            ConfigValue::Prefix(_) => ConfigKey::Prefix,
+            ConfigValue::TimeoutDuration(_) => ConfigKey::TimeoutDuration,
+            ConfigValue::WarningDays(_) => ConfigKey::WarningDays,
+            ConfigValue::PurgeMax(_) => ConfigKey::PurgeMax,
+            ConfigValue::EmbedColor(_) => ConfigKey::EmbedColor,
+            ConfigValue::CommandSuggestions(_) => ConfigKey::CommandSuggestions,
+        }
+    }
+}
+
+impl GuildConfig {
+    /// Render the current value of a key.
+    pub fn display(&self, key: ConfigKey) -> String {
+        match key {
+            ConfigKey::Prefix => self.prefix.clone(),
+            ConfigKey::TimeoutDuration => format_duration_seconds(self.timeout_secs),
+            ConfigKey::WarningDays => self.warning_days.to_string(),
+            ConfigKey::PurgeMax => self.purge_max.to_string(),
+            ConfigKey::EmbedColor => format!("#{:06X}", self.embed_color),
+            ConfigKey::CommandSuggestions => switch_label(self.command_suggestions).to_owned(),
+        }
+    }
+
+    pub fn set(&mut self, value: ConfigValue) {
+        match value {
+            ConfigValue::Prefix(prefix) => self.prefix = prefix,
+            ConfigValue::TimeoutDuration(secs) => self.timeout_secs = secs,
+            ConfigValue::WarningDays(days) => self.warning_days = days,
+            ConfigValue::PurgeMax(amount) => self.purge_max = amount,
+            ConfigValue::EmbedColor(color) => self.embed_color = color,
+            ConfigValue::CommandSuggestions(enabled) => self.command_suggestions = enabled,
+        }
+    }
+}
+
+static DEFAULTS: OnceLock<StdRwLock<GuildConfig>> = OnceLock::new();
+
+fn defaults_lock() -> &'static StdRwLock<GuildConfig> {
+    DEFAULTS.get_or_init(|| StdRwLock::new(GuildConfig::default()))
+}
+
+/// Settings guilds fall back to for keys they haven't changed.
+pub fn defaults() -> GuildConfig {
+    defaults_lock()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Replace the bot-wide defaults, e.g. from the bot config file.
+pub fn set_defaults(config: GuildConfig) {
+    *defaults_lock()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = config;
+}
+
+/// Values guilds set themselves; keys they haven't set follow [`defaults`].
+static GUILD_CONFIGS: OnceLock<RwLock<HashMap<u64, HashMap<ConfigKey, ConfigValue>>>> =
+    OnceLock::new();
+
+fn config_store() -> &'static RwLock<HashMap<u64, HashMap<ConfigKey, ConfigValue>>> {
+    GUILD_CONFIGS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Current settings of a guild, falling back to defaults for direct messages.
+pub async fn guild_config(guild_id: Option<u64>) -> GuildConfig {
+    let mut config = defaults();
+    let Some(guild_id) = guild_id else {
+        return config;
+    };
+
+    if let Some(values) = config_store().read().await.get(&guild_id) {
+        for value in values.values() {
+            config.set(value.clone());
+        }
+    }
+
+    config
+}
+
+/// Keys a guild has set itself, even to the default value.
+pub async fn custom_keys(guild_id: u64) -> Vec<ConfigKey> {
+    config_store()
+        .read()
+        .await
+        .get(&guild_id)
+        .map(|values| values.keys().copied().collect())
+        .unwrap_or_default()
+}
+
+/// Store a validated value for a guild.
+pub async fn set_config_value(guild_id: u64, value: ConfigValue) {
+    let mut store = config_store().write().await;
+    store
+        .entry(guild_id)
+        .or_default()
+        .insert(value.key(), value);
+}
+
+/// Restore a key to the bot-wide default for a guild.
+pub async fn reset_config_value(guild_id: u64, key: ConfigKey) {
+    let mut store = config_store().write().await;
+    if let Some(values) = store.get_mut(&guild_id) {
+        values.remove(&key);
+        if values.is_empty() {
+            store.remove(&guild_id);
+        }
+    }
+}
+
+/// Parse `#RRGGBB`, `0xRRGGBB` or bare `RRGGBB`.
+fn parse_hex_color(raw: &str) -> Option<u32> {
+    let hex = raw
+        .strip_prefix('#')
+        .or_else(|| raw.strip_prefix("0x"))
+        .unwrap_or(raw);
+
+    if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Parse `on`/`off`, also accepting `true`/`false` and `yes`/`no`.
+fn parse_switch(raw: &str) -> Option<bool> {
+    match raw.to_ascii_lowercase().as_str() {
+        "on" | "true" | "yes" => Some(true),
+        "off" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn switch_label(enabled: bool) -> &'static str {
+    if enabled { "on" } else { "off" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reloaded_defaults_reach_keys_a_guild_never_set() {
+        let guild_id = 40;
+        set_config_value(guild_id, ConfigValue::Prefix("?".to_owned())).await;
+
+        set_defaults(GuildConfig {
+            prefix: "$".to_owned(),
+            purge_max: 50,
+            embed_color: 0x12_34_56,
+            ..GuildConfig::default()
+        });
+
+        let config = guild_config(Some(guild_id)).await;
+        assert_eq!(config.prefix, "?");
+        assert_eq!(config.purge_max, 50);
+        assert_eq!(config.embed_color, 0x12_34_56);
+        assert_eq!(custom_keys(guild_id).await, vec![ConfigKey::Prefix]);
+
+        reset_config_value(guild_id, ConfigKey::Prefix).await;
+        assert_eq!(guild_config(Some(guild_id)).await.prefix, "$");
+        assert!(custom_keys(guild_id).await.is_empty());
+    }
+}
@@ -1,2 +1,9 @@
 /// Database-like state stores shared by command handlers.
+pub mod aliases;
+pub mod blacklist;
+pub mod command_access;
+pub mod command_policy;
+pub mod command_stats;
+pub mod cooldowns;
+pub mod guild_config;
 pub mod warnings;
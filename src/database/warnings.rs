@@ -18,14 +18,22 @@ pub struct WarningRecord {
     pub warn_number: usize,
 }
 
-static WARNING_LOGS: OnceLock<RwLock<HashMap<u64, Vec<WarningEntry>>>> = OnceLock::new();
+/// Warnings keyed by guild ID and target user ID, so each server keeps its own.
+type WarningLog = HashMap<(u64, u64), Vec<WarningEntry>>;
 
-fn warning_store() -> &'static RwLock<HashMap<u64, Vec<WarningEntry>>> {
+static WARNING_LOGS: OnceLock<RwLock<WarningLog>> = OnceLock::new();
+
+fn warning_store() -> &'static RwLock<WarningLog> {
     WARNING_LOGS.get_or_init(|| RwLock::new(HashMap::new()))
 }
 
-/// Record a warning for a target user and return the new warning number.
-pub async fn record_warning(user_id: u64, moderator_id: u64, reason: &str) -> WarningRecord {
+/// Record a warning for a target user in a guild and return the new warning number.
+pub async fn record_warning(
+    guild_id: u64,
+    user_id: u64,
+    moderator_id: u64,
+    reason: &str,
+) -> WarningRecord {
     let warned_at = now_unix_secs();
 
     let entry = WarningEntry {
@@ -35,7 +43,7 @@ pub async fn record_warning(user_id: u64, moderator_id: u64, reason: &str) -> Wa
     };
 
     let mut store = warning_store().write().await;
-    let entries = store.entry(user_id).or_default();
+    let entries = store.entry((guild_id, user_id)).or_default();
     entries.push(entry);
 
     WarningRecord {
@@ -43,11 +51,11 @@ pub async fn record_warning(user_id: u64, moderator_id: u64, reason: &str) -> Wa
     }
 }
 
-/// Return warning entries for a target user in the inclusive [since, now] range.
-pub async fn warnings_since(user_id: u64, since: u64) -> Vec<WarningEntry> {
+/// Return a guild's warning entries for a target user in the inclusive [since, now] range.
+pub async fn warnings_since(guild_id: u64, user_id: u64, since: u64) -> Vec<WarningEntry> {
     let store = warning_store().read().await;
     let mut entries = store
-        .get(&user_id)
+        .get(&(guild_id, user_id))
         .cloned()
         .unwrap_or_default()
         .into_iter()
@@ -58,6 +66,16 @@ pub async fn warnings_since(user_id: u64, since: u64) -> Vec<WarningEntry> {
     entries
 }
 
+/// Delete a guild's warnings of a target user and return how many there were.
+///
+/// Warnings other servers issued to the same user are kept.
+pub async fn clear_warnings(guild_id: u64, user_id: u64) -> usize {
+    let mut store = warning_store().write().await;
+    store
+        .remove(&(guild_id, user_id))
+        .map_or(0, |entries| entries.len())
+}
+
 pub fn now_unix_secs() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -0,0 +1,244 @@
+//! Failures with a cause users can act on.
+//!
+//! Discord request failures are classified by Discord's error code so replies
+//! can say what actually went wrong. Handlers propagate them with `?` and
+//! `.context(..)`; the command middleware logs the full chain and replies with
+//! [`BotError::chain_message`].
+
+use std::{error::Error as StdError, fmt, time::Duration};
+
+use twilight_http::{
+    api_error::ApiError,
+    error::{Error as HttpError, ErrorType},
+};
+use twilight_validate::request::{ValidationError, ValidationErrorType};
+
+/// Discord error codes with a dedicated [`BotError`] variant.
+mod codes {
+    pub const UNKNOWN_CHANNEL: u64 = 10003;
+    pub const UNKNOWN_MEMBER: u64 = 10007;
+    pub const UNKNOWN_USER: u64 = 10013;
+    pub const UNKNOWN_BAN: u64 = 10026;
+    pub const MISSING_ACCESS: u64 = 50001;
+    pub const MISSING_PERMISSIONS: u64 = 50013;
+}
+
+/// A failed Discord request, classified by why it failed.
+///
+/// Every variant keeps the original error as its source for logging.
+#[derive(Debug)]
+pub enum BotError {
+    /// The bot lacks a permission, or its highest role is below the target's.
+    ///
+    /// `hint` says what the failed action needs; see
+    /// [`BotError::with_permission_hint`].
+    MissingPermissions {
+        source: HttpError,
+        hint: Option<&'static str>,
+    },
+    /// No channel the bot can see has that ID.
+    UnknownChannel(HttpError),
+    /// The target isn't a member of the server.
+    UnknownMember(HttpError),
+    /// No user has that ID.
+    UnknownUser(HttpError),
+    /// The target isn't banned.
+    UnknownBan(HttpError),
+    /// A timeout would end more than 28 days from now.
+    MaxTimeoutExceeded(HttpError),
+    /// Discord refused the request until `retry_after` has passed.
+    RateLimited {
+        retry_after: Duration,
+        source: HttpError,
+    },
+    /// Anything else Discord or the HTTP client refused.
+    Discord(HttpError),
+}
+
+impl BotError {
+    /// Say what the action needs, for when Discord reports missing permissions.
+    ///
+    /// Other errors are returned unchanged.
+    pub fn with_permission_hint(mut self, needed: &'static str) -> Self {
+        if let Self::MissingPermissions { hint, .. } = &mut self {
+            *hint = Some(needed);
+        }
+        self
+    }
+
+    /// What to tell the user about the first Discord failure in `error`'s chain.
+    ///
+    /// Also covers request errors a handler propagated without converting them.
+    pub fn chain_message(error: &anyhow::Error) -> Option<String> {
+        find_in_chain(error).map(|(kind, hint)| kind.message(hint))
+    }
+
+    /// Whether `error`'s chain holds a Discord failure caused by the request
+    /// itself rather than the bot or Discord.
+    pub fn chain_is_expected(error: &anyhow::Error) -> bool {
+        find_in_chain(error).is_some_and(|(kind, _)| kind.is_expected())
+    }
+
+    fn kind(&self) -> Kind {
+        match self {
+            Self::MissingPermissions { .. } => Kind::MissingPermissions,
+            Self::UnknownChannel(_) => Kind::UnknownChannel,
+            Self::UnknownMember(_) => Kind::UnknownMember,
+            Self::UnknownUser(_) => Kind::UnknownUser,
+            Self::UnknownBan(_) => Kind::UnknownBan,
+            Self::MaxTimeoutExceeded(_) => Kind::MaxTimeoutExceeded,
+            Self::RateLimited { retry_after, .. } => Kind::RateLimited(*retry_after),
+            Self::Discord(_) => Kind::Discord,
+        }
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::MissingPermissions { hint, .. } => *hint,
+            _ => None,
+        }
+    }
+
+    fn source_error(&self) -> &HttpError {
+        match self {
+            Self::MissingPermissions { source, .. }
+            | Self::UnknownChannel(source)
+            | Self::UnknownMember(source)
+            | Self::UnknownUser(source)
+            | Self::UnknownBan(source)
+            | Self::MaxTimeoutExceeded(source)
+            | Self::RateLimited { source, .. }
+            | Self::Discord(source) => source,
+        }
+    }
+}
+
+/// The first [`BotError`] or raw request error in `error`'s chain, with its hint.
+fn find_in_chain(error: &anyhow::Error) -> Option<(Kind, Option<&'static str>)> {
+    error.chain().find_map(|cause| {
+        if let Some(bot_error) = cause.downcast_ref::<BotError>() {
+            return Some((bot_error.kind(), bot_error.hint()));
+        }
+
+        cause
+            .downcast_ref::<HttpError>()
+            .map(|source| (Kind::of(source), None))
+    })
+}
+
+/// Which [`BotError`] variant a request error belongs to, decided without
+/// taking ownership of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    MissingPermissions,
+    UnknownChannel,
+    UnknownMember,
+    UnknownUser,
+    UnknownBan,
+    MaxTimeoutExceeded,
+    RateLimited(Duration),
+    Discord,
+}
+
+impl Kind {
+    fn of(source: &HttpError) -> Self {
+        match source.kind() {
+            ErrorType::Response {
+                error: ApiError::General(general),
+                ..
+            } => match general.code {
+                codes::MISSING_PERMISSIONS | codes::MISSING_ACCESS => Self::MissingPermissions,
+                codes::UNKNOWN_CHANNEL => Self::UnknownChannel,
+                codes::UNKNOWN_MEMBER => Self::UnknownMember,
+                codes::UNKNOWN_USER => Self::UnknownUser,
+                codes::UNKNOWN_BAN => Self::UnknownBan,
+                _ => Self::Discord,
+            },
+            ErrorType::Response {
+                error: ApiError::Ratelimited(ratelimited),
+                ..
+            } => Self::RateLimited(
+                Duration::try_from_secs_f64(ratelimited.retry_after).unwrap_or_default(),
+            ),
+            // Twilight rejects over-long timeouts before sending the request.
+            ErrorType::Validation if is_timeout_too_long(source) => Self::MaxTimeoutExceeded,
+            _ => Self::Discord,
+        }
+    }
+
+    fn message(self, hint: Option<&str>) -> String {
+        match (self, hint) {
+            (Self::MissingPermissions, None) => "I don't have permission to do that.".to_owned(),
+            (Self::MissingPermissions, Some(hint)) => {
+                format!("I don't have permission to do that. {}", hint)
+            }
+            (Self::UnknownChannel, _) => "That channel doesn't exist.".to_owned(),
+            (Self::UnknownMember, _) => "That user isn't a member of this server.".to_owned(),
+            (Self::UnknownUser, _) => "That user doesn't exist.".to_owned(),
+            (Self::UnknownBan, _) => "That user isn't banned.".to_owned(),
+            (Self::MaxTimeoutExceeded, _) => "Timeouts can last at most 28 days.".to_owned(),
+            (Self::RateLimited(retry_after), _) => format!(
+                "Discord is rate limiting me. Try again in {}s.",
+                retry_after.as_secs().max(1)
+            ),
+            (Self::Discord, _) => "Discord couldn't complete that request.".to_owned(),
+        }
+    }
+
+    fn is_expected(self) -> bool {
+        self != Self::Discord
+    }
+}
+
+impl From<HttpError> for BotError {
+    fn from(source: HttpError) -> Self {
+        match Kind::of(&source) {
+            Kind::MissingPermissions => Self::MissingPermissions { source, hint: None },
+            Kind::UnknownChannel => Self::UnknownChannel(source),
+            Kind::UnknownMember => Self::UnknownMember(source),
+            Kind::UnknownUser => Self::UnknownUser(source),
+            Kind::UnknownBan => Self::UnknownBan(source),
+            Kind::MaxTimeoutExceeded => Self::MaxTimeoutExceeded(source),
+            Kind::RateLimited(retry_after) => Self::RateLimited {
+                retry_after,
+                source,
+            },
+            Kind::Discord => Self::Discord(source),
+        }
+    }
+}
+
+fn is_timeout_too_long(source: &HttpError) -> bool {
+    source
+        .source()
+        .and_then(|inner| inner.downcast_ref::<ValidationError>())
+        .is_some_and(|validation| {
+            matches!(
+                validation.kind(),
+                ValidationErrorType::CommunicationDisabledUntil { .. }
+            )
+        })
+}
+
+impl fmt::Display for BotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.kind() {
+            Kind::MissingPermissions => "missing permissions",
+            Kind::UnknownChannel => "unknown channel",
+            Kind::UnknownMember => "unknown member",
+            Kind::UnknownUser => "unknown user",
+            Kind::UnknownBan => "unknown ban",
+            Kind::MaxTimeoutExceeded => "timeout longer than 28 days",
+            Kind::RateLimited(_) => "rate limited",
+            Kind::Discord => "discord request failed",
+        };
+
+        f.write_str(label)
+    }
+}
+
+impl StdError for BotError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(self.source_error())
+    }
+}
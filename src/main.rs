@@ -1,70 +1,373 @@
-use std::env;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use tracing::{error, info};
-use twilight_gateway::{EventTypeFlags, Intents, Shard, ShardId, StreamExt as _};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinSet;
+use tracing::{error, info, warn};
+use tracing_subscriber::{EnvFilter, filter::LevelFilter, fmt, prelude::*, reload};
+use twilight_gateway::{
+    CloseFrame, Config, EventTypeFlags, Shard, ShardState, StreamExt as _, create_iterator,
+    create_recommended,
+};
 use twilight_http::Client;
 use twilight_model::gateway::event::Event;
 
 use rustls::crypto::ring::default_provider;
 
+mod cache;
 mod commands;
+mod config;
 mod context;
 mod database;
+mod error;
+mod shards;
 mod util;
 
-use crate::context::Context;
+use crate::config::{BotConfig, ConfigHandle};
+use crate::context::{Context, EventInfo};
+use crate::shards::{ShardRegistry, ShardStatus};
+use crate::util::pagination::flush_component_cleanups;
+
+/// How long in-flight handlers may keep running once shutdown starts.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
+    // Load the .env file, then the config file with environment overrides
+    dotenvy::dotenv().ok();
+    let bot_config = BotConfig::load()?;
+
+    // RUST_LOG, when set, overrides the configured log level
+    let env_filter = EnvFilter::try_from_default_env().ok();
+    let initial_level = match env_filter {
+        Some(_) => LevelFilter::TRACE,
+        None => LevelFilter::from_level(bot_config.log_level),
+    };
+    let (level_filter, log_level) = reload::Layer::new(initial_level);
+    let log_level = env_filter.is_none().then_some(log_level);
+    tracing_subscriber::registry()
+        .with(level_filter)
+        .with(env_filter)
+        .with(fmt::layer())
+        .init();
+    info!(
+        file = ?bot_config.path,
+        owners = bot_config.owners.len(),
+        data_dir = %bot_config.data_dir.display(),
+        "loaded bot config"
+    );
+
+    // Build the command list now so a duplicate name fails at startup, not on the first message
+    let commands = commands::registry::registry().iter().count();
+    info!(commands, "registered commands");
+
+    let config_handle = ConfigHandle::new(bot_config, log_level);
+    let bot_config = config_handle.current();
+
+    database::blacklist::load(&bot_config.blacklist_path()).await?;
 
     default_provider()
         .install_default()
         .map_err(|_| anyhow::anyhow!("failed to install rustls ring provider"))?;
 
-    // Load the .env file
-    dotenvy::dotenv().ok();
+    // Create a single shared HTTP Client
+    let http = Arc::new(Client::new(bot_config.token.clone()));
 
-    // Store Discord Bot Token
-    let token = env::var("DISCORD_TOKEN")?;
+    // A shard is one Gateway WebSocket connection to Discord
+    // Use the configured shard count, or ask Discord how many we should run
+    let config = Config::new(bot_config.token.clone(), bot_config.intents);
+    let shards: Vec<Shard> = match bot_config.shard_count {
+        Some(total) => {
+            create_iterator(0..total, total, config, |_, builder| builder.build()).collect()
+        }
+        None => create_recommended(&http, config, |_, builder| builder.build())
+            .await?
+            .collect(),
+    };
 
-    // Create a single shared HTTP Client
-    let http = Arc::new(Client::new(token.clone()));
-    let ctx = Context::new(Arc::clone(&http));
+    // Needed to recognize mentions of the bot as a command prefix
+    let bot_user_id = http.current_user().await?.model().await?.id;
 
-    // Declare which intents the bot has
-    let intents = Intents::GUILDS | Intents::GUILD_MESSAGES | Intents::MESSAGE_CONTENT;
+    let total_shards = shards.first().map_or(1, |shard| shard.id().total());
+    let registry = ShardRegistry::new(total_shards);
+    let ctx = Context::new(
+        Arc::clone(&http),
+        registry.clone(),
+        bot_user_id,
+        config_handle,
+    );
 
-    // A shard is one Gateway WebSocket connection to Discord
-    // Declare how many shards we want to be running and input our token and intents
-    let mut shard = Shard::new(ShardId::new(0, 1), token, intents);
+    #[cfg(unix)]
+    tokio::spawn(reload_on_hangup(ctx.config.clone()));
+
+    // Every shard feeds the same dispatcher below
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+    let (close_tx, close_rx) = watch::channel(false);
+    let mut shard_tasks = JoinSet::new();
+
+    for shard in shards {
+        shard_tasks.spawn(run_shard(
+            shard,
+            registry.clone(),
+            event_tx.clone(),
+            close_rx.clone(),
+        ));
+    }
+    drop(event_tx);
+
+    // Handlers run as tasks so shutdown can wait for the ones still in flight
+    let mut handlers = JoinSet::new();
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
 
-    info!("Rusty is connecting...");
+    info!(shards = total_shards, "Rusty is connecting...");
 
     // Our ears, listens for stuff to do
-    while let Some(item) = shard.next_event(EventTypeFlags::all()).await {
-        let event = match item {
-            Ok(event) => event,
-            Err(source) => {
-                error!(?source, "gateway event stream error");
-                continue;
+    loop {
+        let (event_info, event) = tokio::select! {
+            () = &mut shutdown => break,
+            () = ctx.shutdown.notified() => {
+                info!("shutdown requested by a bot owner");
+                break;
             }
+            item = event_rx.recv() => match item {
+                Some(item) => item,
+                None => break,
+            },
         };
 
+        // Reap finished handlers so the set doesn't grow unbounded
+        while handlers.try_join_next().is_some() {}
+
+        ctx.cache.update(&event).await;
+
         match event {
             Event::Ready(_) => {
-                info!("Rusty has successfully awoken!");
+                info!(
+                    shard = event_info.shard_id.number(),
+                    "Rusty has successfully awoken!"
+                );
             }
 
+            Event::GuildCreate(guild) => {
+                let ctx = ctx.with_event(event_info);
+                let guild_id = guild.id();
+                handlers.spawn(async move {
+                    if let Err(source) =
+                        commands::owner::blacklist::leave_if_blacklisted(&ctx, guild_id).await
+                    {
+                        error!(?source, "failed to leave blacklisted guild");
+                    }
+                });
+            }
             Event::MessageCreate(msg) => {
-                commands::handle_message(ctx.clone(), msg).await?;
+                let ctx = ctx.with_event(event_info);
+                handlers.spawn(async move {
+                    if let Err(source) = commands::handle_message(ctx, msg).await {
+                        error!(?source, "message handler failed");
+                    }
+                });
             }
             Event::InteractionCreate(interaction) => {
-                commands::handle_interaction(ctx.clone(), interaction).await?;
+                let ctx = ctx.with_event(event_info);
+                handlers.spawn(async move {
+                    if let Err(source) = commands::handle_interaction(ctx, interaction).await {
+                        error!(?source, "interaction handler failed");
+                    }
+                });
             }
             _ => {} // Ignore unused events
         }
     }
+
+    info!("Rusty is shutting down...");
+
+    // Stop accepting events; shards drop whatever arrives from here on
+    drop(event_rx);
+
+    drain_handlers(&mut handlers).await;
+
+    let stripped = flush_component_cleanups(&http).await;
+    info!(stripped, "removed components from live paginated messages");
+
+    let _ = close_tx.send(true);
+    let closed = tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, async {
+        while shard_tasks.join_next().await.is_some() {}
+    })
+    .await;
+
+    if closed.is_err() {
+        warn!("shards did not close before the shutdown deadline");
+        shard_tasks.shutdown().await;
+    }
+
+    info!("Rusty has shut down cleanly.");
+
     Ok(()) // Return Success, shutdown cleanly
 }
+
+/// Drive a single shard, forwarding its events to the dispatcher until closed.
+async fn run_shard(
+    mut shard: Shard,
+    registry: ShardRegistry,
+    events: mpsc::UnboundedSender<(EventInfo, Event)>,
+    mut close: watch::Receiver<bool>,
+) {
+    let shard_id = shard.id();
+    let mut last_status = None;
+
+    loop {
+        let item = tokio::select! {
+            _ = close.changed() => break,
+            item = shard.next_event(EventTypeFlags::all()) => item,
+        };
+
+        let Some(item) = item else {
+            break;
+        };
+        let received_at = Instant::now();
+
+        report_status(&mut shard, &registry, &mut last_status).await;
+
+        let event = match item {
+            Ok(event) => event,
+            Err(source) => {
+                error!(
+                    ?source,
+                    shard = shard_id.number(),
+                    "gateway event stream error"
+                );
+                continue;
+            }
+        };
+
+        // The dispatcher is gone during shutdown; late events are dropped
+        let info = EventInfo {
+            shard_id,
+            received_at,
+        };
+        let _ = events.send((info, event));
+    }
+
+    close_shard(&mut shard).await;
+    report_status(&mut shard, &registry, &mut last_status).await;
+}
+
+/// Publish the shard's state and latency when either has changed.
+async fn report_status(
+    shard: &mut Shard,
+    registry: &ShardRegistry,
+    last_status: &mut Option<(ShardState, Option<Duration>)>,
+) {
+    // Snapshot synchronously; `Shard` is not `Sync` so no borrow may cross the await
+    let shard_id = shard.id();
+    let state = shard.state();
+    let latency = shard.latency().recent().first().copied();
+
+    if *last_status == Some((state, latency)) {
+        return;
+    }
+
+    *last_status = Some((state, latency));
+    registry
+        .update(shard_id, ShardStatus { state, latency })
+        .await;
+}
+
+/// Reload the config file whenever the process receives SIGHUP.
+#[cfg(unix)]
+async fn reload_on_hangup(config: ConfigHandle) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(stream) => stream,
+        Err(source) => {
+            error!(?source, "failed to listen for SIGHUP");
+            return;
+        }
+    };
+
+    while hangups.recv().await.is_some() {
+        match config.reload() {
+            Ok(changes) if changes.is_empty() => info!("received SIGHUP, config unchanged"),
+            Ok(changes) => info!(?changes, "received SIGHUP, reloaded config"),
+            Err(source) => error!(?source, "received SIGHUP, keeping current config"),
+        }
+    }
+}
+
+/// Resolve once the process receives SIGINT (Ctrl+C) or SIGTERM.
+async fn shutdown_signal() {
+    let interrupt = async {
+        if let Err(source) = tokio::signal::ctrl_c().await {
+            error!(?source, "failed to listen for SIGINT");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut stream) => {
+                stream.recv().await;
+            }
+            Err(source) => {
+                error!(?source, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = interrupt => info!("received SIGINT"),
+        () = terminate => info!("received SIGTERM"),
+    }
+}
+
+/// Wait for in-flight handlers, aborting whatever is left after the grace period.
+async fn drain_handlers(handlers: &mut JoinSet<()>) {
+    if handlers.is_empty() {
+        return;
+    }
+
+    info!(in_flight = handlers.len(), "waiting for in-flight handlers");
+
+    let drained = tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, async {
+        while handlers.join_next().await.is_some() {}
+    })
+    .await;
+
+    if drained.is_err() {
+        warn!(
+            aborted = handlers.len(),
+            "handlers did not finish before the shutdown deadline"
+        );
+        handlers.shutdown().await;
+    }
+}
+
+/// Send a normal close frame and read the shard until Discord acknowledges it.
+async fn close_shard(shard: &mut Shard) {
+    shard.close(CloseFrame::NORMAL);
+
+    let closed = tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, async {
+        while let Some(item) = shard.next_event(EventTypeFlags::empty()).await {
+            if let Ok(Event::GatewayClose(_)) = item {
+                break;
+            }
+        }
+    })
+    .await;
+
+    if closed.is_err() {
+        warn!(
+            shard = shard.id().number(),
+            "shard did not acknowledge the close frame in time"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use tokio::sync::RwLock;
+use twilight_gateway::{ShardId, ShardState};
+use twilight_model::id::{Id, marker::GuildMarker};
+
+/// Last observed connection status of a single shard.
+#[derive(Clone, Copy, Debug)]
+pub struct ShardStatus {
+    pub state: ShardState,
+    /// Most recent heartbeat round-trip, if one has completed.
+    pub latency: Option<Duration>,
+}
+
+/// Per-shard status shared between shard tasks and command handlers.
+///
+/// Cheap to clone because it only stores reference-counted shared state.
+#[derive(Clone)]
+pub struct ShardRegistry {
+    total: u32,
+    statuses: Arc<RwLock<BTreeMap<u32, ShardStatus>>>,
+}
+
+impl ShardRegistry {
+    /// Create a registry for `total` shards, all starting out disconnected.
+    pub fn new(total: u32) -> Self {
+        let statuses = (0..total)
+            .map(|number| {
+                let status = ShardStatus {
+                    state: ShardState::Disconnected {
+                        reconnect_attempts: 0,
+                    },
+                    latency: None,
+                };
+                (number, status)
+            })
+            .collect();
+
+        Self {
+            total,
+            statuses: Arc::new(RwLock::new(statuses)),
+        }
+    }
+
+    /// Shard responsible for a guild, or shard 0 for direct messages.
+    pub fn shard_for_guild(&self, guild_id: Option<Id<GuildMarker>>) -> ShardId {
+        let number = guild_id.map_or(0, |id| ((id.get() >> 22) % u64::from(self.total)) as u32);
+        ShardId::new(number, self.total)
+    }
+
+    /// Record the latest status reported by a shard.
+    pub async fn update(&self, shard_id: ShardId, status: ShardStatus) {
+        self.statuses
+            .write()
+            .await
+            .insert(shard_id.number(), status);
+    }
+
+    /// Status of every shard, ordered by shard number.
+    pub async fn statuses(&self) -> Vec<(u32, ShardStatus)> {
+        self.statuses
+            .read()
+            .await
+            .iter()
+            .map(|(number, status)| (*number, *status))
+            .collect()
+    }
+
+    /// Status of a single shard.
+    pub async fn status(&self, shard_id: ShardId) -> Option<ShardStatus> {
+        self.statuses.read().await.get(&shard_id.number()).copied()
+    }
+}
+
+/// Short human-readable label for a shard state.
+pub fn state_label(state: ShardState) -> &'static str {
+    match state {
+        ShardState::Active => "active",
+        ShardState::Disconnected { .. } => "disconnected",
+        ShardState::FatallyClosed => "fatally closed",
+        ShardState::Identifying => "identifying",
+        ShardState::Resuming => "resuming",
+    }
+}
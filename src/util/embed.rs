@@ -1,22 +1,24 @@
 use twilight_model::channel::message::embed::Embed;
 use twilight_util::builder::embed::{EmbedBuilder, EmbedFooterBuilder};
 
-/// Default embed color used across the bot UI.
+/// Built-in embed color, used until the bot config or a guild sets another.
 pub const DEFAULT_EMBED_COLOR: u32 = 0x90_54_30;
 
 /// Build a standard paginated embed with consistent styling.
 pub fn build_paginated_embed(
     title: &str,
+    color: u32,
     description: impl Into<String>,
     page: usize,
     total_pages: usize,
 ) -> anyhow::Result<Embed> {
-    build_paginated_embed_with_footer_note(title, description, page, total_pages, None)
+    build_paginated_embed_with_footer_note(title, color, description, page, total_pages, None)
 }
 
 /// Build a standard paginated embed with an optional footer suffix.
 pub fn build_paginated_embed_with_footer_note(
     title: &str,
+    color: u32,
     description: impl Into<String>,
     page: usize,
     total_pages: usize,
@@ -39,7 +41,7 @@ pub fn build_paginated_embed_with_footer_note(
 
     let builder = EmbedBuilder::new()
         .title(title)
-        .color(DEFAULT_EMBED_COLOR)
+        .color(color)
         .description(description);
 
     let embed = if footer_text.is_empty() {
@@ -1,7 +1,7 @@
 /// Generic embed builders shared across commands.
 pub mod embed;
-/// Single source of truth for the message-command prefix.
-pub const COMMAND_PREFIX: char = '!';
+/// Message-command prefix used until a guild configures its own.
+pub const DEFAULT_COMMAND_PREFIX: &str = "!";
 /// Shared pagination helpers and interaction utilities.
 pub mod pagination;
 /// Pure parser helpers.
@@ -1,7 +1,21 @@
 //! Stable facade for pagination helpers used by command handlers.
 
-/// Default timeout for button-based pagination sessions.
-pub const DEFAULT_TIMEOUT_SECS: u64 = 120;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Timeout for button-based pagination sessions unless the bot config sets one.
+pub const DEFAULT_PAGINATION_TIMEOUT_SECS: u64 = 120;
+
+static TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_PAGINATION_TIMEOUT_SECS);
+
+/// Timeout for button-based pagination sessions.
+pub fn timeout_secs() -> u64 {
+    TIMEOUT_SECS.load(Ordering::Relaxed)
+}
+
+/// Change the pagination timeout for sessions started from now on.
+pub fn set_timeout_secs(secs: u64) {
+    TIMEOUT_SECS.store(secs, Ordering::Relaxed);
+}
 
 mod components;
 pub mod interaction;
@@ -15,12 +29,10 @@ pub use interaction::{
     validate_interaction_for_command, validate_interaction_for_command_prefix,
     validate_jump_modal_for_command, validate_jump_modal_for_command_prefix,
 };
-pub use page::{
-    clamp_page, page_window, parse_one_based_page, resolve_modal_target_page, total_pages,
-};
+pub use page::{clamp_page, page_window, resolve_modal_target_page, total_pages};
 pub use respond::{
-    open_jump_modal_from_token, respond_ephemeral_message, send_paginated_message,
-    update_paginated_interaction_message,
+    flush_component_cleanups, open_jump_modal_from_token, respond_ephemeral_message,
+    send_paginated_message, update_paginated_interaction_message,
 };
 pub use view::{
     build_paginated_list_view, build_paginated_view, build_paginated_view_with_footer_note,
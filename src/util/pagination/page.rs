@@ -32,16 +32,6 @@ pub fn page_window(total_items: usize, per_page: usize, page: usize) -> (usize,
     (start.min(total_items), end)
 }
 
-/// Parse a one-based page argument.
-///
-/// Returns `Some(page)` when the value is valid (`>= 1`), otherwise `None`.
-pub fn parse_one_based_page(raw: Option<&str>) -> Option<usize> {
-    match raw {
-        Some(value) => value.parse::<usize>().ok().filter(|page| *page >= 1),
-        None => Some(1),
-    }
-}
-
 /// Build a bullet-list description string for a specific page window.
 pub fn paginated_bulleted_description(items: &[String], per_page: usize, page: usize) -> String {
     let total = total_pages(items.len(), per_page);
@@ -24,7 +24,13 @@ use twilight_util::builder::InteractionResponseDataBuilder;
 
 use super::token::{PaginationToken, build_modal_custom_id};
 
-type CleanupTaskMap = HashMap<u64, tokio::task::JoinHandle<()>>;
+struct CleanupTask {
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+type CleanupTaskMap = HashMap<u64, CleanupTask>;
 
 fn cleanup_tasks() -> &'static tokio::sync::Mutex<CleanupTaskMap> {
     static TASKS: OnceLock<tokio::sync::Mutex<CleanupTaskMap>> = OnceLock::new();
@@ -227,21 +233,54 @@ pub async fn schedule_component_cleanup(
 
     let mut tasks = cleanup_tasks().lock().await;
     if let Some(existing_task) = tasks.remove(&message_key) {
-        existing_task.abort();
+        existing_task.handle.abort();
     }
 
     let cleanup_task = tokio::spawn(async move {
         tokio::time::sleep(Duration::from_secs(delay_secs)).await;
 
-        let empty_components: [Component; 0] = [];
-        let _ = http
-            .update_message(channel_id, message_id)
-            .components(Some(&empty_components))
-            .await;
+        strip_components(&http, channel_id, message_id).await;
 
         let mut tasks = cleanup_tasks().lock().await;
         tasks.remove(&message_key);
     });
 
-    tasks.insert(message_key, cleanup_task);
+    tasks.insert(
+        message_key,
+        CleanupTask {
+            channel_id,
+            message_id,
+            handle: cleanup_task,
+        },
+    );
+}
+
+/// Cancel all scheduled cleanups and strip components from their messages now.
+///
+/// Used during shutdown so live paginated messages don't keep dead buttons.
+/// Returns the number of messages that were cleaned up.
+pub async fn flush_component_cleanups(http: &Client) -> usize {
+    let pending = {
+        let mut tasks = cleanup_tasks().lock().await;
+        tasks.drain().map(|(_, task)| task).collect::<Vec<_>>()
+    };
+
+    for task in &pending {
+        task.handle.abort();
+        strip_components(http, task.channel_id, task.message_id).await;
+    }
+
+    pending.len()
+}
+
+async fn strip_components(
+    http: &Client,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) {
+    let empty_components: [Component; 0] = [];
+    let _ = http
+        .update_message(channel_id, message_id)
+        .components(Some(&empty_components))
+        .await;
 }
@@ -9,9 +9,11 @@ use super::components::build_nav_components;
 use super::page::{clamp_page, paginated_bulleted_description, total_pages};
 
 /// Build a generic paginated list view (embed + navigation buttons).
+#[allow(clippy::too_many_arguments)]
 pub fn build_paginated_list_view(
     command: &str,
     title: &str,
+    color: u32,
     items: &[String],
     page: usize,
     per_page: usize,
@@ -25,6 +27,7 @@ pub fn build_paginated_list_view(
     build_paginated_view(
         command,
         title,
+        color,
         description,
         page,
         total,
@@ -34,9 +37,11 @@ pub fn build_paginated_list_view(
 }
 
 /// Build a paginated embed + navigation controls from a pre-rendered description.
+#[allow(clippy::too_many_arguments)]
 pub fn build_paginated_view(
     command: &str,
     title: &str,
+    color: u32,
     description: String,
     page: usize,
     total_pages: usize,
@@ -46,6 +51,7 @@ pub fn build_paginated_view(
     build_paginated_view_with_footer_note(
         command,
         title,
+        color,
         description,
         page,
         total_pages,
@@ -62,6 +68,7 @@ pub fn build_paginated_view(
 pub fn build_paginated_view_with_footer_note(
     command: &str,
     title: &str,
+    color: u32,
     description: String,
     page: usize,
     total_pages: usize,
@@ -75,12 +82,13 @@ pub fn build_paginated_view_with_footer_note(
     let embed = match footer_note {
         Some(note) => build_paginated_embed_with_footer_note(
             title,
+            color,
             description,
             page,
             total_pages,
             Some(note),
         )?,
-        None => build_paginated_embed(title, description, page, total_pages)?,
+        None => build_paginated_embed(title, color, description, page, total_pages)?,
     };
 
     let components = build_nav_components(command, page, total_pages, owner_user_id, timeout_secs);
@@ -1,4 +1,10 @@
-use twilight_model::id::{Id, marker::UserMarker};
+use twilight_model::{
+    guild::Role,
+    id::{
+        Id,
+        marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+    },
+};
 
 /// Parse a target user from a raw argument (`<@id>`, `<@!id>`, or raw ID).
 pub fn parse_target_user_id(raw: &str) -> Option<Id<UserMarker>> {
@@ -13,7 +19,7 @@ pub fn parse_target_user_id(raw: &str) -> Option<Id<UserMarker>> {
         trimmed
     };
 
-    let id = numeric.parse::<u64>().ok()?;
+    let id = numeric.parse::<u64>().ok().filter(|id| *id != 0)?;
 
     Some(Id::new(id))
 }
@@ -44,3 +50,140 @@ pub fn parse_duration_seconds(raw: &str) -> Option<u64> {
 
     number.checked_mul(multiplier)
 }
+
+/// Render seconds in the compact form accepted by [`parse_duration_seconds`].
+///
+/// Uses the largest unit that divides the value evenly, e.g. `600` -> `10m`.
+pub fn format_duration_seconds(secs: u64) -> String {
+    const UNITS: [(u64, char); 3] = [(60 * 60 * 24, 'd'), (60 * 60, 'h'), (60, 'm')];
+
+    UNITS
+        .into_iter()
+        .find(|(size, _)| secs >= *size && secs.is_multiple_of(*size))
+        .map_or_else(
+            || format!("{secs}s"),
+            |(size, unit)| format!("{}{unit}", secs / size),
+        )
+}
+
+/// Parse a role from a raw argument (`<@&id>` or raw ID).
+pub fn parse_role_id(raw: &str) -> Option<Id<RoleMarker>> {
+    let trimmed = raw.trim();
+
+    let numeric = match trimmed.strip_prefix("<@&") {
+        Some(inner) => inner.strip_suffix('>')?,
+        None => trimmed,
+    };
+
+    let id = numeric.parse::<u64>().ok().filter(|id| *id != 0)?;
+
+    Some(Id::new(id))
+}
+
+/// Parse a channel from a raw argument (`<#id>` or raw ID).
+pub fn parse_channel_id(raw: &str) -> Option<Id<ChannelMarker>> {
+    let trimmed = raw.trim();
+
+    let numeric = match trimmed.strip_prefix("<#") {
+        Some(inner) => inner.strip_suffix('>')?,
+        None => trimmed,
+    };
+
+    let id = numeric.parse::<u64>().ok().filter(|id| *id != 0)?;
+
+    Some(Id::new(id))
+}
+
+/// Parse a guild from a raw ID.
+pub fn parse_guild_id(raw: &str) -> Option<Id<GuildMarker>> {
+    let id = raw.trim().parse::<u64>().ok().filter(|id| *id != 0)?;
+
+    Some(Id::new(id))
+}
+
+/// Find a role by mention, raw ID, or case-insensitive name.
+pub fn find_role<'a>(roles: &'a [Role], raw: &str) -> Option<&'a Role> {
+    if let Some(role_id) = parse_role_id(raw) {
+        return roles.iter().find(|role| role.id == role_id);
+    }
+
+    let wanted = raw.trim().trim_start_matches('@');
+    roles.iter().find(|role| {
+        role.name
+            .trim_start_matches('@')
+            .eq_ignore_ascii_case(wanted)
+    })
+}
+
+/// Edit distance between two words, counting a swap of neighbouring letters as one edit.
+///
+/// Used to suggest commands for typos like `!bna` or `!warnigns`.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // rows[i][j] is the distance between the first i chars of `a` and j chars of `b`.
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + substitution);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+
+            rows[i][j] = best;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_of_equal_words_is_zero() {
+        assert_eq!(edit_distance("ban", "ban"), 0);
+        assert_eq!(edit_distance("", ""), 0);
+    }
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("bna", "ban"), 1);
+        assert_eq!(edit_distance("warnigns", "warnings"), 1);
+        assert_eq!(edit_distance("kik", "kick"), 1);
+        assert_eq!(edit_distance("bans", "ban"), 1);
+        assert_eq!(edit_distance("pung", "ping"), 1);
+    }
+
+    #[test]
+    fn edit_distance_against_empty_is_length() {
+        assert_eq!(edit_distance("", "help"), 4);
+        assert_eq!(edit_distance("help", ""), 4);
+    }
+
+    #[test]
+    fn edit_distance_is_symmetric_and_counts_chars() {
+        assert_eq!(edit_distance("timeout", "untimeout"), 2);
+        assert_eq!(edit_distance("untimeout", "timeout"), 2);
+        assert_eq!(edit_distance("héllo", "hello"), 1);
+    }
+
+    #[test]
+    fn edit_distance_does_not_chain_swaps() {
+        // Optimal string alignment: a swapped pair can't be edited again.
+        assert_eq!(edit_distance("ca", "abc"), 3);
+    }
+}
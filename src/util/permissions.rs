@@ -1,5 +1,22 @@
-use twilight_http::Client;
-use twilight_model::{gateway::payload::incoming::MessageCreate, guild::Permissions};
+use twilight_model::{
+    channel::permission_overwrite::{PermissionOverwrite, PermissionOverwriteType},
+    gateway::payload::incoming::MessageCreate,
+    guild::{Permissions, Role},
+    id::{
+        Id,
+        marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+    },
+    util::Timestamp,
+};
+
+use crate::cache::CachedMember;
+use crate::context::Context;
+use crate::database::command_access::{AccessMode, command_access};
+use crate::database::warnings::now_unix_secs;
+
+/// Permissions a timed-out member keeps; everything else is implicitly denied.
+const TIMED_OUT_PERMISSIONS: Permissions =
+    Permissions::VIEW_CHANNEL.union(Permissions::READ_MESSAGE_HISTORY);
 
 /// Convert a permission bitset into a sorted display list.
 ///
@@ -18,51 +35,772 @@ pub fn permission_names(perms: Permissions) -> Vec<String> {
     names
 }
 
-/// Resolve the invoking author's effective guild permissions for a message command.
+/// Everything needed to compute a member's permissions without I/O.
+pub struct PermissionInputs<'a> {
+    pub guild_id: Id<GuildMarker>,
+    pub owner_id: Id<UserMarker>,
+    pub user_id: Id<UserMarker>,
+    pub roles: &'a [Role],
+    pub member: &'a CachedMember,
+    /// Overwrites of the channel to resolve in, or `None` for guild-level permissions.
+    pub overwrites: Option<&'a [PermissionOverwrite]>,
+}
+
+/// Compute a member's effective permissions following Discord's algorithm.
+///
+/// Order: owner, `@everyone` and member roles, administrator, channel
+/// overwrites (`@everyone`, then roles, then the member), then the implicit
+/// denial applied to timed-out members.
+pub fn compute_permissions(inputs: &PermissionInputs<'_>) -> Permissions {
+    if inputs.user_id == inputs.owner_id {
+        return Permissions::all();
+    }
+
+    let everyone_role_id = inputs.guild_id.cast::<RoleMarker>();
+    let mut perms = Permissions::empty();
+
+    for role in inputs.roles {
+        if role.id == everyone_role_id || inputs.member.roles.contains(&role.id) {
+            perms |= role.permissions;
+        }
+    }
+
+    if perms.contains(Permissions::ADMINISTRATOR) {
+        return Permissions::all();
+    }
+
+    if let Some(overwrites) = inputs.overwrites {
+        perms = apply_overwrites(perms, inputs, overwrites);
+    }
+
+    if is_timed_out(inputs.member.communication_disabled_until) {
+        perms &= TIMED_OUT_PERMISSIONS;
+    }
+
+    perms
+}
+
+/// Where a member's final state for a single permission came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PermissionSource {
+    /// The member owns the guild.
+    Owner,
+    /// A role grants `ADMINISTRATOR`.
+    Administrator,
+    /// Granted by `@everyone` or one of the member's roles.
+    Roles,
+    /// The channel's `@everyone` overwrite.
+    EveryoneOverwrite,
+    /// A channel overwrite for one of the member's roles.
+    RoleOverwrite(Id<RoleMarker>),
+    /// The channel overwrite for the member themselves.
+    MemberOverwrite,
+    /// Implicitly denied because the member is timed out.
+    Timeout,
+}
+
+/// The final state of a single permission and what decided it.
+#[derive(Clone, Debug)]
+pub struct PermissionExplanation {
+    pub name: String,
+    pub granted: bool,
+    pub source: PermissionSource,
+}
+
+/// Explain, per permission, how [`compute_permissions`] arrived at its result.
+///
+/// Lists every granted permission, plus permissions denied by a channel
+/// overwrite or a timeout. Permissions the member simply never had are omitted.
+pub fn explain_permissions(inputs: &PermissionInputs<'_>) -> Vec<PermissionExplanation> {
+    if inputs.user_id == inputs.owner_id {
+        return vec![PermissionExplanation {
+            name: "ADMINISTRATOR".to_owned(),
+            granted: true,
+            source: PermissionSource::Owner,
+        }];
+    }
+
+    let everyone_role_id = inputs.guild_id.cast::<RoleMarker>();
+    let mut base = Permissions::empty();
+    for role in inputs.roles {
+        if role.id == everyone_role_id || inputs.member.roles.contains(&role.id) {
+            base |= role.permissions;
+        }
+    }
+
+    if base.contains(Permissions::ADMINISTRATOR) {
+        return vec![PermissionExplanation {
+            name: "ADMINISTRATOR".to_owned(),
+            granted: true,
+            source: PermissionSource::Administrator,
+        }];
+    }
+
+    let timed_out = is_timed_out(inputs.member.communication_disabled_until);
+    let mut explanations = Vec::new();
+
+    for (name, flag) in Permissions::all().iter_names() {
+        let mut granted = base.contains(flag);
+        let mut source = granted.then_some(PermissionSource::Roles);
+
+        if let Some(overwrites) = inputs.overwrites {
+            for (overwrite_granted, overwrite_source) in
+                overwrite_decisions(inputs, overwrites, flag)
+            {
+                granted = overwrite_granted;
+                source = Some(overwrite_source);
+            }
+        }
+
+        if timed_out && granted && !TIMED_OUT_PERMISSIONS.contains(flag) {
+            granted = false;
+            source = Some(PermissionSource::Timeout);
+        }
+
+        if let Some(source) = source {
+            explanations.push(PermissionExplanation {
+                name: name.to_owned(),
+                granted,
+                source,
+            });
+        }
+    }
+
+    explanations.sort_by(|left, right| left.name.cmp(&right.name));
+    explanations
+}
+
+/// Overwrite decisions for one permission flag, in the order Discord applies them.
+fn overwrite_decisions(
+    inputs: &PermissionInputs<'_>,
+    overwrites: &[PermissionOverwrite],
+    flag: Permissions,
+) -> Vec<(bool, PermissionSource)> {
+    let everyone_id = inputs.guild_id.get();
+    let mut decisions = Vec::new();
+
+    if let Some(everyone) = overwrites.iter().find(|overwrite| {
+        overwrite.kind == PermissionOverwriteType::Role && overwrite.id.get() == everyone_id
+    }) {
+        if everyone.deny.contains(flag) {
+            decisions.push((false, PermissionSource::EveryoneOverwrite));
+        }
+        if everyone.allow.contains(flag) {
+            decisions.push((true, PermissionSource::EveryoneOverwrite));
+        }
+    }
+
+    let member_role_overwrites = overwrites.iter().filter(|overwrite| {
+        overwrite.kind == PermissionOverwriteType::Role
+            && inputs.member.roles.contains(&overwrite.id.cast())
+    });
+
+    // Role overwrites are combined: any allow beats any deny.
+    let mut role_deny = None;
+    let mut role_allow = None;
+    for overwrite in member_role_overwrites {
+        if role_deny.is_none() && overwrite.deny.contains(flag) {
+            role_deny = Some(PermissionSource::RoleOverwrite(overwrite.id.cast()));
+        }
+        if role_allow.is_none() && overwrite.allow.contains(flag) {
+            role_allow = Some(PermissionSource::RoleOverwrite(overwrite.id.cast()));
+        }
+    }
+    if let Some(source) = role_deny {
+        decisions.push((false, source));
+    }
+    if let Some(source) = role_allow {
+        decisions.push((true, source));
+    }
+
+    if let Some(member) = overwrites.iter().find(|overwrite| {
+        overwrite.kind == PermissionOverwriteType::Member && overwrite.id.cast() == inputs.user_id
+    }) {
+        if member.deny.contains(flag) {
+            decisions.push((false, PermissionSource::MemberOverwrite));
+        }
+        if member.allow.contains(flag) {
+            decisions.push((true, PermissionSource::MemberOverwrite));
+        }
+    }
+
+    decisions
+}
+
+fn apply_overwrites(
+    mut perms: Permissions,
+    inputs: &PermissionInputs<'_>,
+    overwrites: &[PermissionOverwrite],
+) -> Permissions {
+    let everyone_id = inputs.guild_id.get();
+
+    if let Some(everyone) = overwrites.iter().find(|overwrite| {
+        overwrite.kind == PermissionOverwriteType::Role && overwrite.id.get() == everyone_id
+    }) {
+        perms &= !everyone.deny;
+        perms |= everyone.allow;
+    }
+
+    let mut role_allow = Permissions::empty();
+    let mut role_deny = Permissions::empty();
+    for overwrite in overwrites {
+        if overwrite.kind == PermissionOverwriteType::Role
+            && inputs.member.roles.contains(&overwrite.id.cast())
+        {
+            role_allow |= overwrite.allow;
+            role_deny |= overwrite.deny;
+        }
+    }
+    perms &= !role_deny;
+    perms |= role_allow;
+
+    if let Some(member) = overwrites.iter().find(|overwrite| {
+        overwrite.kind == PermissionOverwriteType::Member && overwrite.id.cast() == inputs.user_id
+    }) {
+        perms &= !member.deny;
+        perms |= member.allow;
+    }
+
+    perms
+}
+
+/// Whether a member's `communication_disabled_until` is still in the future.
+pub fn is_timed_out(until: Option<Timestamp>) -> bool {
+    until.is_some_and(|until| until.as_secs() > now_unix_secs() as i64)
+}
+
+/// Resolve a member's effective permissions, optionally within a channel.
+///
+/// Consults the gateway cache first and falls back to HTTP for missing data.
+pub async fn resolve_member_permissions(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    member: &CachedMember,
+    channel_id: Option<Id<ChannelMarker>>,
+) -> anyhow::Result<Permissions> {
+    let guild_state = GuildPermissionState::fetch(ctx, guild_id, channel_id).await?;
+
+    Ok(compute_permissions(
+        &guild_state.inputs(guild_id, user_id, member),
+    ))
+}
+
+/// Resolve and explain a member's permissions, optionally within a channel.
+pub async fn explain_member_permissions(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    member: &CachedMember,
+    channel_id: Option<Id<ChannelMarker>>,
+) -> anyhow::Result<Vec<PermissionExplanation>> {
+    let guild_state = GuildPermissionState::fetch(ctx, guild_id, channel_id).await?;
+
+    Ok(explain_permissions(
+        &guild_state.inputs(guild_id, user_id, member),
+    ))
+}
+
+/// Guild-wide data shared by every member's permission calculation.
+struct GuildPermissionState {
+    owner_id: Id<UserMarker>,
+    roles: Vec<Role>,
+    overwrites: Option<Vec<PermissionOverwrite>>,
+}
+
+impl GuildPermissionState {
+    async fn fetch(
+        ctx: &Context,
+        guild_id: Id<GuildMarker>,
+        channel_id: Option<Id<ChannelMarker>>,
+    ) -> anyhow::Result<Self> {
+        let owner_id = guild_owner_id(ctx, guild_id).await?;
+        let roles = guild_roles(ctx, guild_id).await?;
+        let overwrites = match channel_id {
+            Some(channel_id) => Some(channel_overwrites(ctx, channel_id).await?),
+            None => None,
+        };
+
+        Ok(Self {
+            owner_id,
+            roles,
+            overwrites,
+        })
+    }
+
+    fn inputs<'a>(
+        &'a self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        member: &'a CachedMember,
+    ) -> PermissionInputs<'a> {
+        PermissionInputs {
+            guild_id,
+            owner_id: self.owner_id,
+            user_id,
+            roles: &self.roles,
+            member,
+            overwrites: self.overwrites.as_deref(),
+        }
+    }
+}
+
+/// Resolve the invoking author's effective permissions in the message's channel.
 ///
 /// Returns `Ok(None)` when the message is not from a guild context.
 pub async fn resolve_message_author_permissions(
-    http: &Client,
+    ctx: &Context,
     msg: &MessageCreate,
 ) -> anyhow::Result<Option<Permissions>> {
-    if let Some(perms) = msg.member.as_ref().and_then(|m| m.permissions) {
-        return Ok(Some(perms));
-    }
-
     let Some(guild_id) = msg.guild_id else {
         return Ok(None);
     };
 
-    let member = http
-        .guild_member(guild_id, msg.author.id)
+    let member = match msg.member.as_ref() {
+        Some(member) => CachedMember {
+            roles: member.roles.clone(),
+            communication_disabled_until: member.communication_disabled_until,
+        },
+        None => guild_member(ctx, guild_id, msg.author.id).await?,
+    };
+
+    let perms =
+        resolve_member_permissions(ctx, guild_id, msg.author.id, &member, Some(msg.channel_id))
+            .await?;
+
+    Ok(Some(perms))
+}
+
+/// Fetch every role of a guild, from the cache when available.
+pub async fn guild_roles(ctx: &Context, guild_id: Id<GuildMarker>) -> anyhow::Result<Vec<Role>> {
+    if let Some(roles) = ctx.cache.roles(guild_id).await {
+        return Ok(roles);
+    }
+
+    let roles = ctx.http.roles(guild_id).await?.model().await?;
+    ctx.cache.set_roles(guild_id, &roles).await;
+
+    Ok(roles)
+}
+
+/// Fetch a guild member, from the cache when available.
+pub async fn guild_member(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> anyhow::Result<CachedMember> {
+    if let Some(member) = ctx.cache.member(guild_id, user_id).await {
+        return Ok(member);
+    }
+
+    let member = ctx
+        .http
+        .guild_member(guild_id, user_id)
         .await?
         .model()
         .await?;
+    ctx.cache.insert_member(guild_id, &member).await;
+
+    Ok(CachedMember {
+        roles: member.roles,
+        communication_disabled_until: member.communication_disabled_until,
+    })
+}
 
-    let roles = http.roles(guild_id).await?.model().await?;
+/// Fetch the owner of a guild, from the cache when available.
+pub async fn guild_owner_id(
+    ctx: &Context,
+    guild_id: Id<GuildMarker>,
+) -> anyhow::Result<Id<UserMarker>> {
+    if let Some(guild) = ctx.cache.guild(guild_id).await {
+        return Ok(guild.owner_id);
+    }
 
-    let mut resolved = Permissions::empty();
+    let guild = ctx.http.guild(guild_id).await?.model().await?;
+    ctx.cache.insert_guild(&guild).await;
 
-    for role in roles {
-        if role.id == guild_id.cast() || member.roles.contains(&role.id) {
-            resolved |= role.permissions;
-        }
+    Ok(guild.owner_id)
+}
+
+/// Fetch the permission overwrites that apply in a channel.
+///
+/// Threads don't carry overwrites, so their parent channel's are used.
+pub async fn channel_overwrites(
+    ctx: &Context,
+    channel_id: Id<ChannelMarker>,
+) -> anyhow::Result<Vec<PermissionOverwrite>> {
+    let channel = fetch_channel(ctx, channel_id).await?;
+
+    let channel = match channel.parent_id {
+        Some(parent_id) if channel.kind.is_thread() => fetch_channel(ctx, parent_id).await?,
+        _ => channel,
+    };
+
+    Ok(channel.permission_overwrites.unwrap_or_default())
+}
+
+/// Fetch a channel or thread, from the cache when available.
+pub async fn fetch_channel(
+    ctx: &Context,
+    channel_id: Id<ChannelMarker>,
+) -> anyhow::Result<twilight_model::channel::Channel> {
+    if let Some(channel) = ctx.cache.channel(channel_id).await {
+        return Ok(channel);
     }
 
-    Ok(Some(resolved))
+    let channel = ctx.http.channel(channel_id).await?.model().await?;
+    ctx.cache.insert_channel(&channel).await;
+
+    Ok(channel)
 }
 
-/// Check whether the message author has a required permission (or administrator).
+/// Check whether the message author has a required permission in the message's channel.
 ///
 /// Returns `Ok(false)` when the message is outside a guild context.
 pub async fn has_message_permission(
-    http: &Client,
+    ctx: &Context,
     msg: &MessageCreate,
     required: Permissions,
 ) -> anyhow::Result<bool> {
-    let Some(perms) = resolve_message_author_permissions(http, msg).await? else {
+    let Some(perms) = resolve_message_author_permissions(ctx, msg).await? else {
         return Ok(false);
     };
 
     Ok(perms.contains(Permissions::ADMINISTRATOR) || perms.contains(required))
 }
+
+/// Check whether the message author may run a command.
+///
+/// Roles granted access through `!access` always pass. Otherwise the command's
+/// default permission applies, unless the guild replaced it with its granted
+/// roles, in which case only administrators pass.
+pub async fn has_command_permission(
+    ctx: &Context,
+    msg: &MessageCreate,
+    command: &str,
+    default: Permissions,
+) -> anyhow::Result<bool> {
+    let Some(guild_id) = msg.guild_id else {
+        return Ok(false);
+    };
+
+    let access = command_access(guild_id.get(), command).await;
+    let holds_granted_role = msg.member.as_ref().is_some_and(|member| {
+        member
+            .roles
+            .iter()
+            .any(|role_id| access.roles.contains(&role_id.get()))
+    });
+    if holds_granted_role {
+        return Ok(true);
+    }
+
+    let required = match access.mode {
+        AccessMode::Replace if !access.roles.is_empty() => Permissions::ADMINISTRATOR,
+        _ => default,
+    };
+
+    has_message_permission(ctx, msg, required).await
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{
+        guild::{RoleColors, RoleFlags},
+        util::Timestamp,
+    };
+
+    use super::*;
+
+    const GUILD: u64 = 1;
+    const OWNER: u64 = 2;
+    const USER: u64 = 3;
+    const MODS: u64 = 10;
+    const MUTED: u64 = 11;
+
+    #[allow(deprecated)]
+    fn role(id: u64, permissions: Permissions) -> Role {
+        Role {
+            color: 0,
+            colors: RoleColors {
+                primary_color: 0,
+                secondary_color: None,
+                tertiary_color: None,
+            },
+            hoist: false,
+            icon: None,
+            id: Id::new(id),
+            managed: false,
+            mentionable: false,
+            name: format!("role {id}"),
+            permissions,
+            position: 0,
+            flags: RoleFlags::empty(),
+            tags: None,
+            unicode_emoji: None,
+        }
+    }
+
+    fn member(roles: &[u64]) -> CachedMember {
+        CachedMember {
+            roles: roles.iter().map(|id| Id::new(*id)).collect(),
+            communication_disabled_until: None,
+        }
+    }
+
+    fn overwrite(
+        id: u64,
+        kind: PermissionOverwriteType,
+        allow: Permissions,
+        deny: Permissions,
+    ) -> PermissionOverwrite {
+        PermissionOverwrite {
+            allow,
+            deny,
+            id: Id::new(id),
+            kind,
+        }
+    }
+
+    fn inputs<'a>(
+        user_id: u64,
+        roles: &'a [Role],
+        member: &'a CachedMember,
+        overwrites: Option<&'a [PermissionOverwrite]>,
+    ) -> PermissionInputs<'a> {
+        PermissionInputs {
+            guild_id: Id::new(GUILD),
+            owner_id: Id::new(OWNER),
+            user_id: Id::new(user_id),
+            roles,
+            member,
+            overwrites,
+        }
+    }
+
+    fn base_roles() -> Vec<Role> {
+        vec![
+            role(
+                GUILD,
+                Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+            ),
+            role(MODS, Permissions::MANAGE_MESSAGES),
+            role(MUTED, Permissions::empty()),
+        ]
+    }
+
+    fn source_of(explanations: &[PermissionExplanation], name: &str) -> (bool, PermissionSource) {
+        let explanation = explanations
+            .iter()
+            .find(|explanation| explanation.name == name)
+            .unwrap_or_else(|| panic!("{name} is not explained"));
+
+        (explanation.granted, explanation.source)
+    }
+
+    #[test]
+    fn owner_has_every_permission() {
+        let roles = base_roles();
+        let member = member(&[]);
+        let overwrites = [overwrite(
+            GUILD,
+            PermissionOverwriteType::Role,
+            Permissions::empty(),
+            Permissions::all(),
+        )];
+        let inputs = inputs(OWNER, &roles, &member, Some(&overwrites));
+
+        assert_eq!(compute_permissions(&inputs), Permissions::all());
+        assert_eq!(
+            source_of(&explain_permissions(&inputs), "ADMINISTRATOR"),
+            (true, PermissionSource::Owner)
+        );
+    }
+
+    #[test]
+    fn administrator_ignores_overwrites() {
+        let roles = vec![
+            role(GUILD, Permissions::empty()),
+            role(MODS, Permissions::ADMINISTRATOR),
+        ];
+        let member = member(&[MODS]);
+        let overwrites = [overwrite(
+            USER,
+            PermissionOverwriteType::Member,
+            Permissions::empty(),
+            Permissions::all(),
+        )];
+        let inputs = inputs(USER, &roles, &member, Some(&overwrites));
+
+        assert_eq!(compute_permissions(&inputs), Permissions::all());
+        assert_eq!(
+            source_of(&explain_permissions(&inputs), "ADMINISTRATOR"),
+            (true, PermissionSource::Administrator)
+        );
+    }
+
+    #[test]
+    fn everyone_and_member_roles_combine() {
+        let roles = base_roles();
+        let plain = member(&[]);
+        let moderator = member(&[MODS]);
+
+        assert_eq!(
+            compute_permissions(&inputs(USER, &roles, &plain, None)),
+            Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES
+        );
+        assert_eq!(
+            compute_permissions(&inputs(USER, &roles, &moderator, None)),
+            Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES | Permissions::MANAGE_MESSAGES
+        );
+    }
+
+    #[test]
+    fn everyone_overwrite_applies_before_roles() {
+        let roles = base_roles();
+        let member = member(&[MODS]);
+        let overwrites = [
+            overwrite(
+                GUILD,
+                PermissionOverwriteType::Role,
+                Permissions::empty(),
+                Permissions::SEND_MESSAGES,
+            ),
+            overwrite(
+                MODS,
+                PermissionOverwriteType::Role,
+                Permissions::SEND_MESSAGES,
+                Permissions::empty(),
+            ),
+        ];
+        let inputs = inputs(USER, &roles, &member, Some(&overwrites));
+
+        assert!(compute_permissions(&inputs).contains(Permissions::SEND_MESSAGES));
+        assert_eq!(
+            source_of(&explain_permissions(&inputs), "SEND_MESSAGES"),
+            (true, PermissionSource::RoleOverwrite(Id::new(MODS)))
+        );
+    }
+
+    #[test]
+    fn role_overwrite_allow_beats_role_deny() {
+        let roles = base_roles();
+        let member = member(&[MODS, MUTED]);
+        let overwrites = [
+            overwrite(
+                MUTED,
+                PermissionOverwriteType::Role,
+                Permissions::empty(),
+                Permissions::SEND_MESSAGES,
+            ),
+            overwrite(
+                MODS,
+                PermissionOverwriteType::Role,
+                Permissions::SEND_MESSAGES,
+                Permissions::empty(),
+            ),
+        ];
+        let inputs = inputs(USER, &roles, &member, Some(&overwrites));
+
+        assert!(compute_permissions(&inputs).contains(Permissions::SEND_MESSAGES));
+        assert_eq!(
+            source_of(&explain_permissions(&inputs), "SEND_MESSAGES"),
+            (true, PermissionSource::RoleOverwrite(Id::new(MODS)))
+        );
+    }
+
+    #[test]
+    fn member_overwrite_beats_role_overwrites() {
+        let roles = base_roles();
+        let member = member(&[MODS]);
+        let overwrites = [
+            overwrite(
+                MODS,
+                PermissionOverwriteType::Role,
+                Permissions::SEND_MESSAGES,
+                Permissions::empty(),
+            ),
+            overwrite(
+                USER,
+                PermissionOverwriteType::Member,
+                Permissions::empty(),
+                Permissions::SEND_MESSAGES | Permissions::MANAGE_MESSAGES,
+            ),
+        ];
+        let inputs = inputs(USER, &roles, &member, Some(&overwrites));
+
+        let perms = compute_permissions(&inputs);
+        assert!(!perms.contains(Permissions::SEND_MESSAGES));
+        assert!(!perms.contains(Permissions::MANAGE_MESSAGES));
+        assert!(perms.contains(Permissions::VIEW_CHANNEL));
+
+        let explanations = explain_permissions(&inputs);
+        assert_eq!(
+            source_of(&explanations, "SEND_MESSAGES"),
+            (false, PermissionSource::MemberOverwrite)
+        );
+        assert_eq!(
+            source_of(&explanations, "VIEW_CHANNEL"),
+            (true, PermissionSource::Roles)
+        );
+    }
+
+    #[test]
+    fn overwrites_for_other_roles_and_members_are_ignored() {
+        let roles = base_roles();
+        let member = member(&[]);
+        let overwrites = [
+            overwrite(
+                MODS,
+                PermissionOverwriteType::Role,
+                Permissions::empty(),
+                Permissions::VIEW_CHANNEL,
+            ),
+            overwrite(
+                OWNER,
+                PermissionOverwriteType::Member,
+                Permissions::empty(),
+                Permissions::VIEW_CHANNEL,
+            ),
+        ];
+        let inputs = inputs(USER, &roles, &member, Some(&overwrites));
+
+        assert!(compute_permissions(&inputs).contains(Permissions::VIEW_CHANNEL));
+    }
+
+    #[test]
+    fn timeout_keeps_only_view_and_history() {
+        let roles = vec![role(
+            GUILD,
+            Permissions::VIEW_CHANNEL
+                | Permissions::READ_MESSAGE_HISTORY
+                | Permissions::SEND_MESSAGES,
+        )];
+        let mut member = member(&[]);
+        member.communication_disabled_until =
+            Some(Timestamp::from_secs(now_unix_secs() as i64 + 3600).expect("valid timestamp"));
+        let inputs = inputs(USER, &roles, &member, None);
+
+        assert_eq!(
+            compute_permissions(&inputs),
+            Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY
+        );
+        assert_eq!(
+            source_of(&explain_permissions(&inputs), "SEND_MESSAGES"),
+            (false, PermissionSource::Timeout)
+        );
+    }
+
+    #[test]
+    fn expired_timeout_is_ignored() {
+        let roles = base_roles();
+        let mut member = member(&[]);
+        member.communication_disabled_until =
+            Some(Timestamp::from_secs(now_unix_secs() as i64 - 60).expect("valid timestamp"));
+
+        assert!(
+            compute_permissions(&inputs(USER, &roles, &member, None))
+                .contains(Permissions::SEND_MESSAGES)
+        );
+    }
+}